@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+declare_id!("D71wkSepfMZWWPuguK2S7vgF39vMx5s6RU223A5sBZJh");
+
+// Swaps at whatever rate the caller asks for, funded by a `pool` keypair that
+// signs the outer transaction. Only meant to exercise pathfinder callbacks.
+#[program]
+pub mod mock_swap {
+  use super::*;
+
+  // sells the seized collateral for exactly the quote owed to the market
+  pub fn on_liquidate(
+    ctx: Context<Swap>,
+    repaid_quote: u64,
+    collateral_amount: u64,
+    _data: Vec<u8>,
+  ) -> Result<()> {
+    swap(ctx, collateral_amount, repaid_quote)
+  }
+
+  // sells the released collateral, `data` holds the quote amount to pay out
+  pub fn on_deleverage(ctx: Context<Swap>, collateral_amount: u64, data: Vec<u8>) -> Result<()> {
+    let quote_out = u64::try_from_slice(&data)?;
+    swap(ctx, collateral_amount, quote_out)
+  }
+
+  // buys collateral with the borrowed quote, `data` holds the collateral amount to pay out
+  pub fn on_leverage(ctx: Context<Swap>, quote_amount: u64, data: Vec<u8>) -> Result<()> {
+    let collateral_out = u64::try_from_slice(&data)?;
+    swap_quote(ctx, quote_amount, collateral_out)
+  }
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+  pub user: Signer<'info>,
+  pub pool: Signer<'info>,
+
+  #[account(mut, token::authority = user)]
+  pub user_ata_collateral: Account<'info, TokenAccount>,
+  #[account(mut, token::authority = user)]
+  pub user_ata_quote: Account<'info, TokenAccount>,
+
+  #[account(mut, token::authority = pool, token::mint = user_ata_collateral.mint)]
+  pub pool_ata_collateral: Account<'info, TokenAccount>,
+  #[account(mut, token::authority = pool, token::mint = user_ata_quote.mint)]
+  pub pool_ata_quote: Account<'info, TokenAccount>,
+
+  pub token_program: Program<'info, Token>,
+}
+
+fn swap(ctx: Context<Swap>, collateral_in: u64, quote_out: u64) -> Result<()> {
+  let Swap {
+    user,
+    pool,
+    user_ata_collateral,
+    user_ata_quote,
+    pool_ata_collateral,
+    pool_ata_quote,
+    token_program,
+  } = ctx.accounts;
+
+  transfer(
+    CpiContext::new(
+      token_program.to_account_info(),
+      Transfer {
+        from: user_ata_collateral.to_account_info(),
+        to: pool_ata_collateral.to_account_info(),
+        authority: user.to_account_info(),
+      },
+    ),
+    collateral_in,
+  )?;
+
+  transfer(
+    CpiContext::new(
+      token_program.to_account_info(),
+      Transfer {
+        from: pool_ata_quote.to_account_info(),
+        to: user_ata_quote.to_account_info(),
+        authority: pool.to_account_info(),
+      },
+    ),
+    quote_out,
+  )?;
+
+  Ok(())
+}
+
+fn swap_quote(ctx: Context<Swap>, quote_in: u64, collateral_out: u64) -> Result<()> {
+  let Swap {
+    user,
+    pool,
+    user_ata_collateral,
+    user_ata_quote,
+    pool_ata_collateral,
+    pool_ata_quote,
+    token_program,
+  } = ctx.accounts;
+
+  transfer(
+    CpiContext::new(
+      token_program.to_account_info(),
+      Transfer {
+        from: user_ata_quote.to_account_info(),
+        to: pool_ata_quote.to_account_info(),
+        authority: user.to_account_info(),
+      },
+    ),
+    quote_in,
+  )?;
+
+  transfer(
+    CpiContext::new(
+      token_program.to_account_info(),
+      Transfer {
+        from: pool_ata_collateral.to_account_info(),
+        to: user_ata_collateral.to_account_info(),
+        authority: pool.to_account_info(),
+      },
+    ),
+    collateral_out,
+  )?;
+
+  Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+
+use crate::error::MarketError;
+
+/// Returns the anchor instruction discriminator for `name`, so callbacks can be
+/// implemented as regular anchor instructions on the receiving program.
+pub fn callback_discriminator(name: &str) -> [u8; 8] {
+  let preimage = format!("global:{}", name);
+  let mut discriminator = [0u8; 8];
+  discriminator.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+  discriminator
+}
+
+/// Invokes a caller supplied program passed through remaining accounts.
+/// The first remaining account is the callback program, the rest are forwarded
+/// to it untouched (signer and writable flags are preserved).
+pub fn invoke_callback<T: AnchorSerialize>(
+  remaining_accounts: &[AccountInfo],
+  name: &str,
+  args: &T,
+) -> Result<()> {
+  let (callback_program, callback_accounts) = remaining_accounts
+    .split_first()
+    .ok_or(MarketError::InvalidCallback)?;
+
+  require!(callback_program.executable, MarketError::InvalidCallback);
+  require_keys_neq!(
+    callback_program.key(),
+    crate::ID,
+    MarketError::InvalidCallback
+  );
+
+  let mut data = callback_discriminator(name).to_vec();
+  args.serialize(&mut data)?;
+
+  let instruction = Instruction {
+    program_id: callback_program.key(),
+    accounts: callback_accounts
+      .iter()
+      .map(|ai| AccountMeta {
+        pubkey: ai.key(),
+        is_signer: ai.is_signer,
+        is_writable: ai.is_writable,
+      })
+      .collect(),
+    data,
+  };
+
+  invoke(&instruction, remaining_accounts)?;
+
+  Ok(())
+}
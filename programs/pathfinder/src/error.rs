@@ -60,4 +60,105 @@ pub enum MarketError {
   InvalidOracle,
   #[msg("Stale oracle")]
   StaleOracle,
+
+  // Flash Loan Errors
+  #[msg("Invalid callback program")]
+  InvalidCallback,
+  #[msg("Flash loan was not repaid")]
+  FlashLoanNotRepaid,
+  #[msg("Invalid flash loan")]
+  InvalidFlashLoan,
+
+  // Token Errors
+  #[msg("Mint extension is not supported")]
+  UnsupportedMintExtension,
+
+  // Interest Rate Model Errors
+  #[msg("Invalid interest rate model")]
+  InvalidIrm,
+
+  // Pause Errors
+  #[msg("Market action is paused")]
+  MarketPaused,
+  #[msg("Invalid guardian")]
+  InvalidGuardian,
+
+  // Cap Errors
+  #[msg("Supply cap exceeded")]
+  SupplyCapExceeded,
+  #[msg("Borrow cap exceeded")]
+  BorrowCapExceeded,
+
+  // Oracle Risk Errors
+  #[msg("Invalid oracle config")]
+  InvalidOracleConfig,
+  #[msg("Oracle confidence too wide")]
+  OracleConfidenceTooWide,
+  #[msg("Not enough oracle responses")]
+  InsufficientOracleResponses,
+
+  #[msg("Oracle price is not positive")]
+  OraclePriceNotPositive,
+  #[msg("Oracle account owner is invalid")]
+  InvalidOracleOwner,
+  #[msg("Oracle account does not match the market feed")]
+  InvalidOracleAccount,
+  #[msg("Oracle account data is invalid")]
+  InvalidOracleData,
+
+  // Fallback Oracle Errors
+  #[msg("Invalid fallback oracle")]
+  InvalidFallbackOracle,
+  #[msg("Oracle prices deviate too much")]
+  OracleDeviationTooHigh,
+
+  // Close Errors
+  #[msg("Position is not empty")]
+  PositionNotEmpty,
+
+  // Migration Errors
+  #[msg("Account is already on the latest version")]
+  AlreadyMigrated,
+  #[msg("Account cannot be migrated")]
+  InvalidMigration,
+
+  // LTV Errors
+  #[msg("Max borrow ltv must be below the liquidation ltv")]
+  InvalidLtv,
+
+  // Pre-liquidation Errors
+  #[msg("Invalid pre-liquidation config")]
+  InvalidPreLiquidationConfig,
+  #[msg("Pre-liquidation is disabled for this market")]
+  PreLiquidationDisabled,
+  #[msg("Borrower has not authorized pre-liquidations")]
+  PreLiquidationNotAuthorized,
+  #[msg("Position is not in the pre-liquidation band")]
+  NotInPreLiquidationBand,
+  #[msg("Pre-liquidation close factor exceeded")]
+  PreLiquidationCloseFactorExceeded,
+
+  // Liquidation Auction Errors
+  #[msg("Invalid liquidation auction duration")]
+  InvalidLiquidationAuctionDuration,
+  #[msg("Liquidation auction has not started")]
+  LiquidationAuctionNotStarted,
+
+  // Liquidation Limit Errors
+  #[msg("Invalid liquidation limits")]
+  InvalidLiquidationLimits,
+  #[msg("Liquidation exceeds the close factor or leaves dust debt")]
+  LiquidationLimitExceeded,
+
+  // Leverage Errors
+  #[msg("Swap returned less than the minimum output")]
+  SlippageExceeded,
+  #[msg("Invalid leverage input")]
+  InvalidLeverageInput,
+  #[msg("Position ltv above the requested max")]
+  LeverageLtvExceeded,
+
+  // Bad Debt Errors
+  #[msg("Market deposits were wiped out by bad debt")]
+  MarketWipedOut,
 }
@@ -0,0 +1,382 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+  AdaptiveCurveLimits, FallbackOracle, IrmKind, LiquidationLimits, MarketPause, OracleConfig,
+  OracleSource, PreLiquidationConfig,
+};
+
+// Events are named after the instruction that emits them. Amounts are in
+// token units and totals/indexes describe the market after the instruction.
+// `old_` fields hold the same values before it, ahead of interest accrual.
+
+#[event]
+pub struct CreateMarket {
+  pub market: Pubkey,
+  pub quote_mint: Pubkey,
+  pub collateral_mint: Pubkey,
+  pub max_borrow_ltv: u64,
+  pub liquidation_ltv: u64,
+  pub oracle_id: Pubkey,
+  pub oracle_source: OracleSource,
+  pub quote_oracle_id: Pubkey,
+  pub oracle_config: OracleConfig,
+  pub fallback_oracle: Option<FallbackOracle>,
+  pub irm: IrmKind,
+  pub supply_cap: u64,
+  pub borrow_cap: u64,
+}
+
+#[event]
+pub struct Deposit {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub assets: u64,
+  pub shares: u64,
+  pub old_owner_shares: u64,
+  pub owner_shares: u64,
+  pub old_total_shares: u64,
+  pub total_shares: u64,
+  pub old_deposit_index: u128,
+  pub deposit_index: u128,
+}
+
+#[event]
+pub struct Withdraw {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub recipient: Pubkey,
+  pub assets: u64,
+  pub shares: u64,
+  pub old_owner_shares: u64,
+  pub owner_shares: u64,
+  pub old_total_shares: u64,
+  pub total_shares: u64,
+  pub old_deposit_index: u128,
+  pub deposit_index: u128,
+}
+
+#[event]
+pub struct Borrow {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub recipient: Pubkey,
+  pub assets: u64,
+  pub shares: u64,
+  pub old_owner_borrow_shares: u64,
+  pub owner_borrow_shares: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+  pub old_borrow_index: u128,
+  pub borrow_index: u128,
+}
+
+#[event]
+pub struct Repay {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub assets: u64,
+  pub shares: u64,
+  pub old_owner_borrow_shares: u64,
+  pub owner_borrow_shares: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+  pub old_borrow_index: u128,
+  pub borrow_index: u128,
+}
+
+#[event]
+pub struct Deleverage {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub collateral_amount: u64,
+  pub quote_out: u64,
+  pub repaid_assets: u64,
+  pub repaid_shares: u64,
+  pub old_owner_borrow_shares: u64,
+  pub owner_borrow_shares: u64,
+  pub old_owner_collateral: u64,
+  pub owner_collateral: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+  pub old_total_collateral: u64,
+  pub total_collateral: u64,
+}
+
+#[event]
+pub struct Leverage {
+  pub market: Pubkey,
+  pub owner: Pubkey,
+  pub deposited_collateral: u64,
+  pub borrowed_assets: u64,
+  pub borrowed_shares: u64,
+  pub swapped_collateral: u64,
+  pub old_owner_borrow_shares: u64,
+  pub owner_borrow_shares: u64,
+  pub old_owner_collateral: u64,
+  pub owner_collateral: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+  pub old_total_collateral: u64,
+  pub total_collateral: u64,
+}
+
+#[event]
+pub struct DepositCollateral {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub amount: u64,
+  pub old_owner_collateral: u64,
+  pub owner_collateral: u64,
+  pub old_total_collateral: u64,
+  pub total_collateral: u64,
+}
+
+#[event]
+pub struct WithdrawCollateral {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub owner: Pubkey,
+  pub recipient: Pubkey,
+  pub amount: u64,
+  pub old_owner_collateral: u64,
+  pub owner_collateral: u64,
+  pub old_total_collateral: u64,
+  pub total_collateral: u64,
+}
+
+#[event]
+pub struct Liquidate {
+  pub market: Pubkey,
+  pub liquidator: Pubkey,
+  pub borrower: Pubkey,
+  pub repaid_quote: u64,
+  pub repaid_shares: u64,
+  pub seized_collateral: u64,
+  pub incentive_factor: u128,
+  // protocol cut of the incentive in quote tokens and the fee shares minted for it
+  pub liquidation_fee: u64,
+  pub fee_shares: u64,
+  pub bad_debt: u64,
+  pub bad_debt_shares: u64,
+  pub old_borrower_borrow_shares: u64,
+  pub borrower_borrow_shares: u64,
+  pub old_borrower_collateral: u64,
+  pub borrower_collateral: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+}
+
+#[event]
+pub struct PreLiquidate {
+  pub market: Pubkey,
+  pub liquidator: Pubkey,
+  pub borrower: Pubkey,
+  pub repaid_quote: u64,
+  pub repaid_shares: u64,
+  pub seized_collateral: u64,
+  // both scaled by WAD
+  pub close_factor: u128,
+  pub incentive_factor: u128,
+  pub old_borrower_borrow_shares: u64,
+  pub borrower_borrow_shares: u64,
+  pub old_borrower_collateral: u64,
+  pub borrower_collateral: u64,
+  pub old_total_borrow_shares: u64,
+  pub total_borrow_shares: u64,
+}
+
+#[event]
+pub struct BadDebtRealized {
+  pub market: Pubkey,
+  pub borrower: Pubkey,
+  pub bad_debt_shares: u64,
+  pub bad_debt: u64,
+  pub old_deposit_index: u128,
+  pub deposit_index: u128,
+}
+
+#[event]
+pub struct AccrueInterest {
+  pub market: Pubkey,
+  // average borrow rate per second over the accrual period, scaled by WAD
+  pub avg_rate: u128,
+  pub old_rate_at_target: u128,
+  pub rate_at_target: u128,
+  pub elapsed: u64,
+  pub interest: u64,
+  pub fee_shares: u64,
+  pub old_borrow_index: u128,
+  pub borrow_index: u128,
+  pub old_deposit_index: u128,
+  pub deposit_index: u128,
+}
+
+#[event]
+pub struct WithdrawFee {
+  pub market: Pubkey,
+  pub recipient: Pubkey,
+  pub assets: u64,
+  pub shares: u64,
+  pub old_fee_shares: u64,
+  pub fee_shares: u64,
+}
+
+#[event]
+pub struct InitializeConfig {
+  pub authority: Pubkey,
+  pub fee_recipient: Pubkey,
+  pub curve_limits: AdaptiveCurveLimits,
+}
+
+#[event]
+pub struct UpdateFee {
+  pub old_fee_factor: u64,
+  pub new_fee_factor: u64,
+}
+
+#[event]
+pub struct UpdateLiquidationFee {
+  pub old_liquidation_fee_factor: u64,
+  pub new_liquidation_fee_factor: u64,
+}
+
+#[event]
+pub struct UpdateAuthority {
+  pub old_authority: Pubkey,
+  pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct ProposeAuthority {
+  pub authority: Pubkey,
+  pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct CancelAuthority {
+  pub authority: Pubkey,
+  pub cancelled_authority: Pubkey,
+}
+
+#[event]
+pub struct UpdateRecipient {
+  pub old_recipient: Pubkey,
+  pub new_recipient: Pubkey,
+}
+
+#[event]
+pub struct UpdateCurveLimits {
+  pub old_curve_limits: AdaptiveCurveLimits,
+  pub new_curve_limits: AdaptiveCurveLimits,
+}
+
+#[event]
+pub struct UpdateGuardian {
+  pub old_guardian: Pubkey,
+  pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct SetMarketPause {
+  pub market: Pubkey,
+  pub user: Pubkey,
+  pub paused: MarketPause,
+}
+
+#[event]
+pub struct UpdateMarketCaps {
+  pub market: Pubkey,
+  pub old_supply_cap: u64,
+  pub new_supply_cap: u64,
+  pub old_borrow_cap: u64,
+  pub new_borrow_cap: u64,
+}
+
+#[event]
+pub struct UpdatePreLiquidation {
+  pub market: Pubkey,
+  pub old_pre_liquidation: PreLiquidationConfig,
+  pub new_pre_liquidation: PreLiquidationConfig,
+}
+
+#[event]
+pub struct SetPreLiquidationAuthorization {
+  pub market: Pubkey,
+  pub owner: Pubkey,
+  pub enabled: bool,
+}
+
+#[event]
+pub struct UpdateLiquidationAuction {
+  pub market: Pubkey,
+  pub old_duration: u64,
+  pub new_duration: u64,
+}
+
+#[event]
+pub struct UpdateLiquidationLimits {
+  pub market: Pubkey,
+  pub old_limits: LiquidationLimits,
+  pub new_limits: LiquidationLimits,
+}
+
+#[event]
+pub struct UpdateLiquidationState {
+  pub market: Pubkey,
+  pub borrower: Pubkey,
+  pub insolvent_since: u64,
+}
+
+#[event]
+pub struct SetFallbackOracle {
+  pub market: Pubkey,
+  pub fallback_oracle: Option<FallbackOracle>,
+}
+
+#[event]
+pub struct MigrateConfig {
+  pub authority: Pubkey,
+  pub version: u8,
+  pub curve_limits: AdaptiveCurveLimits,
+}
+
+#[event]
+pub struct MigrateMarket {
+  pub market: Pubkey,
+  pub version: u8,
+  pub oracle_config: OracleConfig,
+  pub irm: IrmKind,
+  pub max_borrow_ltv: u64,
+  pub liquidation_ltv: u64,
+}
+
+#[event]
+pub struct CloseLenderPosition {
+  pub market: Pubkey,
+  pub owner: Pubkey,
+}
+
+#[event]
+pub struct CloseBorrowerPosition {
+  pub market: Pubkey,
+  pub owner: Pubkey,
+}
+
+#[event]
+pub struct CloseDelegate {
+  pub owner: Pubkey,
+  pub delegate: Pubkey,
+}
+
+#[event]
+pub struct UpdateDelegate {
+  pub owner: Pubkey,
+  pub old_delegate: Pubkey,
+  pub new_delegate: Pubkey,
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+  pub fn validate(&self) -> Result<()> {
+    require!(
+      self.config.pending_authority != Pubkey::default()
+        && self.user.key() == self.config.pending_authority,
+      MarketError::InvalidAuthority
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>) -> Result<()> {
+    let AcceptAuthority { config, .. } = ctx.accounts;
+
+    emit!(events::UpdateAuthority {
+      old_authority: config.authority,
+      new_authority: config.pending_authority,
+    });
+
+    config.authority = config.pending_authority;
+    config.pending_authority = Pubkey::default();
+
+    Ok(())
+  }
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::Mint;
 
 use crate::error::MarketError;
+use crate::events;
 use crate::interest_rate::get_rate;
 use crate::math::*;
 use crate::state::*;
@@ -24,7 +25,7 @@ pub struct AccrueInterest<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -32,10 +33,10 @@ pub struct AccrueInterest<'info> {
   pub market: Box<Account<'info, Market>>,
 
   #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 }
 
 impl<'info> AccrueInterest<'info> {
@@ -68,6 +69,10 @@ pub fn accrue_interest(market: &mut Account<Market>, config: &Account<Config>) -
 
   let total_borrows = market.total_borrows()?;
 
+  let old_rate_at_target = market.rate_at_target;
+  let old_borrow_index = market.borrow_index;
+  let old_deposit_index = market.deposit_index;
+
   // Get interest rate from IRM
   let (avg_rate, end_rate_at_target) = get_rate(market)?;
   market.rate_at_target = end_rate_at_target.to_u128()?;
@@ -87,12 +92,13 @@ pub fn accrue_interest(market: &mut Account<Market>, config: &Account<Config>) -
       .to_u128()?;
 
   // Handle fee if set
+  let mut fee_shares = 0;
   if config.fee_factor != 0 {
     let fee_amount = Decimal::from_raw_u64(interest).w_mul_down(Decimal::from_raw_u64(config.fee_factor))?.to_u64()?;
 
     // calculate fee shares using total deposits (prior to applying interest)
     let deposits_sub_fee = market.total_deposits()?.checked_sub(fee_amount).unwrap();
-    let fee_shares = to_shares_down(fee_amount, deposits_sub_fee, market.total_shares)?;
+    fee_shares = to_shares_down(fee_amount, deposits_sub_fee, market.total_shares)?;
 
     // Update fee shares
     market.fee_shares = market
@@ -108,5 +114,19 @@ pub fn accrue_interest(market: &mut Account<Market>, config: &Account<Config>) -
 
   market.last_accrual_timestamp = current_timestamp;
 
+  emit!(events::AccrueInterest {
+    market: market.key(),
+    avg_rate: avg_rate.to_u128()?,
+    old_rate_at_target,
+    rate_at_target: market.rate_at_target,
+    elapsed,
+    interest,
+    fee_shares,
+    old_borrow_index,
+    borrow_index: market.borrow_index,
+    old_deposit_index,
+    deposit_index: market.deposit_index,
+  });
+
   Ok(())
 }
@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
 use crate::math::*;
-use crate::oracle::oracle_get_price;
+use crate::oracle::{oracle_get_price, OracleAccounts};
+use crate::transfer::transfer_from_vault;
 use crate::{accrue_interest::accrue_interest, generate_market_seeds, state::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -50,7 +52,7 @@ pub struct Borrow<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -70,30 +72,39 @@ pub struct Borrow<'info> {
   pub borrower_shares: Box<Account<'info, BorrowerShares>>,
 
   // quote
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = recipient,
     associated_token::mint = quote_mint,
+    associated_token::token_program = token_program,
   )]
-  pub recipient_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub recipient_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   // collateral
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   /// CHECK: needed for dynamic oracle account
   pub oracle_ai: AccountInfo<'info>, // oracle account
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
   pub system_program: Program<'info, System>,
 }
 
@@ -104,17 +115,24 @@ impl<'info> Borrow<'info> {
 
   pub fn handle(ctx: Context<Self>, args: BorrowArgs) -> Result<()> {
     let Borrow {
+      user,
+      recipient,
       config,
       market,
       borrower_shares,
+      quote_mint,
       collateral_mint,
       recipient_ata_quote,
       vault_ata_quote,
       token_program,
       oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
       ..
     } = ctx.accounts;
 
+    require!(!market.paused.borrow, MarketError::MarketPaused);
+
     let mut shares = args.shares;
     let mut assets = args.amount;
 
@@ -125,6 +143,10 @@ impl<'info> Borrow<'info> {
 
     msg!("borrowing {}", assets);
 
+    let old_owner_borrow_shares = borrower_shares.borrow_shares;
+    let old_total_borrow_shares = market.total_borrow_shares;
+    let old_borrow_index = market.borrow_index;
+
     accrue_interest(market, config)?;
 
     let total_borrows = market.total_borrows()?;
@@ -138,9 +160,15 @@ impl<'info> Borrow<'info> {
     // check if user is solvent after borrowing
     let updated_shares = borrower_shares.borrow_shares.checked_add(shares).unwrap();
 
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
     if !is_solvent(
       market,
-      &oracle_ai,
+      &oracle_accounts,
       updated_shares,
       borrower_shares.collateral_amount,
       collateral_mint.decimals,
@@ -160,55 +188,119 @@ impl<'info> Borrow<'info> {
       .checked_add(shares)
       .ok_or(MarketError::MathOverflow)?;
 
+    if market.borrow_cap != 0 {
+      require_gte!(
+        market.borrow_cap,
+        market.total_borrows()?,
+        MarketError::BorrowCapExceeded
+      );
+    }
+
     // transfer tokens to borrower
     let seeds = generate_market_seeds!(market);
     let signer = &[&seeds[..]];
 
-    transfer(
-      CpiContext::new_with_signer(
-        token_program.to_account_info(),
-        Transfer {
-          from: vault_ata_quote.to_account_info(),
-          to: recipient_ata_quote.to_account_info(),
-          authority: market.to_account_info(),
-        },
-        signer,
-      ),
+    transfer_from_vault(
+      token_program,
+      quote_mint,
+      vault_ata_quote,
+      recipient_ata_quote,
+      &market.to_account_info(),
       assets,
+      signer,
     )?;
 
+    emit!(events::Borrow {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      recipient: recipient.key(),
+      assets,
+      shares,
+      old_owner_borrow_shares,
+      owner_borrow_shares: borrower_shares.borrow_shares,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+      old_borrow_index,
+      borrow_index: market.borrow_index,
+    });
+
     Ok(())
   }
 }
 
+/// Returns whether the position can take on its debt, checked against the max borrow ltv.
 pub fn is_solvent(
   market: &Account<Market>,
-  oracle_ai: &AccountInfo,
+  oracle_accounts: &OracleAccounts,
+  borrow_shares: u64,
+  collateral_amount: u64,
+  collateral_decimals: u8,
+) -> Result<bool> {
+  is_within_ltv(
+    market,
+    oracle_accounts,
+    borrow_shares,
+    collateral_amount,
+    collateral_decimals,
+    market.max_borrow_ltv,
+  )
+}
+
+/// Returns whether the position is past the liquidation ltv.
+pub fn is_liquidatable(
+  market: &Account<Market>,
+  oracle_accounts: &OracleAccounts,
   borrow_shares: u64,
   collateral_amount: u64,
   collateral_decimals: u8,
+) -> Result<bool> {
+  Ok(!is_within_ltv(
+    market,
+    oracle_accounts,
+    borrow_shares,
+    collateral_amount,
+    collateral_decimals,
+    market.effective_liquidation_ltv(),
+  )?)
+}
+
+fn is_within_ltv(
+  market: &Account<Market>,
+  oracle_accounts: &OracleAccounts,
+  borrow_shares: u64,
+  collateral_amount: u64,
+  collateral_decimals: u8,
+  ltv: u64,
 ) -> Result<bool> {
   // price is low end of confidence interval
-  let price = oracle_get_price(&market.oracle, &oracle_ai, false)?;
+  let price = oracle_get_price(&market.oracle, oracle_accounts, false)?;
 
   let total_borrows = market.total_borrows()?;
 
   // Calculate borrowed amount by converting borrow shares to assets, rounding up
   let borrowed = to_assets_up(borrow_shares, total_borrows, market.total_borrow_shares)?;
 
-  // Calculate max borrow amount based on collateral value and LTV factor
-  let max_borrow = (collateral_amount as u128)
-    .checked_mul(price.price as u128) // Multiply collateral amount by price
-    .ok_or(MarketError::MathOverflow)?
-    .checked_div(price.scale as u128) // Scale down by oracle price scale
-    .ok_or(MarketError::MathOverflow)?
-    .checked_mul(market.ltv_factor as u128) // Apply LTV factor
-    .ok_or(MarketError::MathOverflow)?
-    .checked_div(10_u128.pow(collateral_decimals as u32)) // Scale by collateral decimals
-    .ok_or(MarketError::MathOverflow)?;
-
-  // User is solvent if max borrow amount >= borrowed amount
-  Ok(max_borrow >= (borrowed as u128))
+  // Calculate collateral value in quote units
+  let collateral_value = collateral_to_quote_down(
+    collateral_amount,
+    &price,
+    collateral_decimals,
+    market.quote_mint_decimals,
+  )?;
+
+  // Position is within the ltv if max debt >= borrowed amount
+  Ok(max_debt(collateral_value, ltv)? >= (borrowed as u128))
+}
+
+/// Returns the debt a collateral value supports at `ltv`, scaled by LTV_PRECISION.
+pub fn max_debt(collateral_value: u64, ltv: u64) -> Result<u128> {
+  Ok(
+    (collateral_value as u128)
+      .checked_mul(ltv as u128)
+      .ok_or(MarketError::MathOverflow)?
+      / LTV_PRECISION,
+  )
 }
 
 pub fn borrow(ctx: Context<Borrow>, args: BorrowArgs) -> Result<()> {
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(Accounts)]
+pub struct CancelAuthority<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuthorityProtection<'info> for CancelAuthority<'info> {}
+
+impl<'info> CancelAuthority<'info> {
+  pub fn validate(&self) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    require!(
+      self.config.pending_authority != Pubkey::default(),
+      MarketError::InvalidAuthority
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>) -> Result<()> {
+    let CancelAuthority { config, .. } = ctx.accounts;
+
+    emit!(events::CancelAuthority {
+      authority: config.authority,
+      cancelled_authority: config.pending_authority,
+    });
+
+    config.pending_authority = Pubkey::default();
+
+    Ok(())
+  }
+}
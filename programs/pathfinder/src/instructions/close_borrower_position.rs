@@ -0,0 +1,53 @@
+use crate::error::MarketError;
+use crate::events;
+use crate::state::{BorrowerShares, Market, BORROWER_SHARES_SEED_PREFIX};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseBorrowerPosition<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub market: Box<Account<'info, Market>>,
+
+  // rent goes back to the owner
+  #[account(
+    mut,
+    close = user,
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Box<Account<'info, BorrowerShares>>,
+}
+
+impl<'info> CloseBorrowerPosition<'info> {
+  pub fn validate(&self) -> Result<()> {
+    require_eq!(
+      self.borrower_shares.borrow_shares,
+      0,
+      MarketError::PositionNotEmpty
+    );
+    require_eq!(
+      self.borrower_shares.collateral_amount,
+      0,
+      MarketError::PositionNotEmpty
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>) -> Result<()> {
+    let CloseBorrowerPosition { user, market, .. } = ctx.accounts;
+
+    emit!(events::CloseBorrowerPosition {
+      market: market.key(),
+      owner: user.key(),
+    });
+
+    Ok(())
+  }
+}
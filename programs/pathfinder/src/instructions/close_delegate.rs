@@ -0,0 +1,42 @@
+use crate::events;
+use crate::state::{PositionDelegate, DELEGATE_SEED_PREFIX};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseDelegate<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  // rent goes back to the owner
+  #[account(
+    mut,
+    close = user,
+    seeds = [
+      DELEGATE_SEED_PREFIX,
+      user.key().as_ref(),
+    ],
+    bump
+  )]
+  pub position_delegate: Box<Account<'info, PositionDelegate>>,
+}
+
+impl<'info> CloseDelegate<'info> {
+  pub fn validate(&self) -> Result<()> {
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>) -> Result<()> {
+    let CloseDelegate {
+      user,
+      position_delegate,
+      ..
+    } = ctx.accounts;
+
+    emit!(events::CloseDelegate {
+      owner: user.key(),
+      delegate: position_delegate.delegate,
+    });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,44 @@
+use crate::error::MarketError;
+use crate::events;
+use crate::state::{LenderShares, Market, MARKET_SHARES_SEED_PREFIX};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseLenderPosition<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub market: Box<Account<'info, Market>>,
+
+  // rent goes back to the owner
+  #[account(
+    mut,
+    close = user,
+    seeds = [
+      MARKET_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump
+  )]
+  pub lender_shares: Box<Account<'info, LenderShares>>,
+}
+
+impl<'info> CloseLenderPosition<'info> {
+  pub fn validate(&self) -> Result<()> {
+    require_eq!(self.lender_shares.shares, 0, MarketError::PositionNotEmpty);
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>) -> Result<()> {
+    let CloseLenderPosition { user, market, .. } = ctx.accounts;
+
+    emit!(events::CloseLenderPosition {
+      market: market.key(),
+      owner: user.key(),
+    });
+
+    Ok(())
+  }
+}
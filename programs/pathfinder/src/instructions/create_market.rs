@@ -1,16 +1,27 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+use crate::error::MarketError;
+use crate::events;
+use crate::interest_rate::validate_irm;
 use crate::math::WAD;
 use crate::oracle::oracle_init;
 use crate::state::*;
+use crate::transfer::validate_mint_extensions;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateMarketArgs {
   pub oracle_id: Pubkey,
   pub oracle_source: OracleSource,
-  pub ltv_factor: u64,
+  pub quote_oracle_id: Pubkey, // composite only
+  pub oracle_config: OracleConfig,
+  pub fallback_oracle: Option<FallbackOracle>,
+  pub max_borrow_ltv: u64,
+  pub liquidation_ltv: u64,
+  pub irm: IrmKind,
+  pub supply_cap: u64,
+  pub borrow_cap: u64,
 }
 
 #[derive(Accounts)]
@@ -29,12 +40,12 @@ pub struct CreateMarket<'info> {
   #[account(
     init,
     payer = user,
-    space = 8 + std::mem::size_of::<Market>(),
+    space = 8 + Market::INIT_SPACE,
     seeds = [
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &args.ltv_factor.to_le_bytes(),
+      &args.max_borrow_ltv.to_le_bytes(),
       &args.oracle_id.to_bytes(),
     ],
     bump,
@@ -43,38 +54,48 @@ pub struct CreateMarket<'info> {
 
   #[account(constraint = quote_mint.is_initialized == true)]
   #[account(
-    constraint = quote_mint.is_initialized == true && collateral_mint.key() != quote_mint.key()
+    constraint = quote_mint.is_initialized == true && collateral_mint.key() != quote_mint.key(),
+    mint::token_program = quote_token_program,
   )]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = market,
-    associated_token::mint = quote_mint
+    associated_token::mint = quote_mint,
+    associated_token::token_program = quote_token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
-    constraint = collateral_mint.is_initialized == true && collateral_mint.key() != quote_mint.key()
+    constraint = collateral_mint.is_initialized == true && collateral_mint.key() != quote_mint.key(),
+    mint::token_program = collateral_token_program,
   )]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = market,
     associated_token::mint = collateral_mint,
+    associated_token::token_program = collateral_token_program,
   )]
-  pub vault_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
   pub associated_token_program: Program<'info, AssociatedToken>,
-  pub token_program: Program<'info, Token>,
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
   pub system_program: Program<'info, System>,
 }
 
 impl<'info> CreateMarket<'info> {
-  pub fn validate(&self) -> Result<()> {
+  pub fn validate(&self, args: &CreateMarketArgs) -> Result<()> {
+    validate_ltvs(args.max_borrow_ltv, args.liquidation_ltv)?;
+    validate_irm(&args.irm, &self.config.curve_limits)?;
+    validate_mint_extensions(&self.quote_mint)?;
+    validate_mint_extensions(&self.collateral_mint)?;
+
     Ok(())
   }
 
@@ -94,6 +115,7 @@ impl<'info> CreateMarket<'info> {
     // create market if it doesn't exist
     market.set_inner(Market {
       bump: ctx.bumps.market,
+      version: MARKET_VERSION,
 
       // deposit accounting
       total_shares: 0,
@@ -107,15 +129,62 @@ impl<'info> CreateMarket<'info> {
       total_collateral: 0,
       collateral_mint: collateral_mint.key(),
       collateral_mint_decimals: collateral_mint.decimals,
-      ltv_factor: args.ltv_factor,
-      oracle: oracle_init(&args.oracle_source, &args.oracle_id)?,
+      max_borrow_ltv: args.max_borrow_ltv,
+      liquidation_ltv: args.liquidation_ltv,
+      pre_liquidation: PreLiquidationConfig::default(),
+      liquidation_auction_duration: 0,
+      liquidation_limits: LiquidationLimits::default(),
+      oracle: oracle_init(
+        &args.oracle_source,
+        &args.oracle_id,
+        &args.quote_oracle_id,
+        &args.oracle_config,
+        args.fallback_oracle,
+      )?,
 
       // interest
+      irm: args.irm,
       last_accrual_timestamp: current_timestamp,
       rate_at_target: 0,
       fee_shares: 0,
+      bad_debt: 0,
+
+      // caps
+      supply_cap: args.supply_cap,
+      borrow_cap: args.borrow_cap,
+
+      paused: MarketPause::default(),
+      seed_ltv: args.max_borrow_ltv,
+      reserved: [0; MARKET_RESERVED_BYTES],
+    });
+
+    emit!(events::CreateMarket {
+      market: market.key(),
+      quote_mint: market.quote_mint,
+      collateral_mint: market.collateral_mint,
+      max_borrow_ltv: market.max_borrow_ltv,
+      liquidation_ltv: market.liquidation_ltv,
+      oracle_id: market.oracle.id,
+      oracle_source: market.oracle.source,
+      quote_oracle_id: market.oracle.quote_id,
+      oracle_config: market.oracle.config,
+      fallback_oracle: market.oracle.fallback,
+      irm: market.irm,
+      supply_cap: market.supply_cap,
+      borrow_cap: market.borrow_cap,
     });
 
     Ok(())
   }
 }
+
+/// Positions must be able to borrow below the liquidation threshold, which
+/// itself cannot exceed the collateral value.
+pub fn validate_ltvs(max_borrow_ltv: u64, liquidation_ltv: u64) -> Result<()> {
+  require!(
+    max_borrow_ltv < liquidation_ltv && liquidation_ltv as u128 <= LTV_PRECISION,
+    MarketError::InvalidLtv
+  );
+
+  Ok(())
+}
@@ -0,0 +1,302 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::callback::invoke_callback;
+use crate::error::MarketError;
+use crate::events;
+use crate::math::*;
+use crate::oracle::OracleAccounts;
+use crate::transfer::{amount_with_transfer_fee, transfer_from_vault, transfer_to_vault};
+use crate::{
+  accrue_interest::accrue_interest, borrow::is_solvent, generate_market_seeds, state::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DeleverageArgs {
+  pub owner: Pubkey,
+  pub collateral_amount: u64,
+  // least quote the swap must return
+  pub min_quote_out: u64,
+  pub data: Vec<u8>,
+}
+
+// data passed to the `on_deleverage` instruction of the swap program
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DeleverageCallbackArgs {
+  pub collateral_amount: u64,
+  pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: DeleverageArgs)]
+pub struct Deleverage<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    constraint = args.owner.key() == user.key() || position_delegate.delegate == user.key() @ MarketError::UnauthorizedDelegate,
+    space = 8 + std::mem::size_of::<PositionDelegate>(),
+    seeds = [
+      DELEGATE_SEED_PREFIX,
+      args.owner.key().as_ref(),
+    ],
+    bump
+  )]
+  pub position_delegate: Box<Account<'info, PositionDelegate>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      quote_mint.key().as_ref(),
+      collateral_mint.key().as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    mut,
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      args.owner.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Box<Account<'info, BorrowerShares>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint,
+    associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+  /// CHECK: needed for dynamic oracle account
+  pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
+
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> Deleverage<'info> {
+  pub fn validate(&self, args: &DeleverageArgs) -> Result<()> {
+    if args.collateral_amount == 0 {
+      return err!(MarketError::InvalidWithdrawInput);
+    }
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<'_, '_, '_, 'info, Self>, args: DeleverageArgs) -> Result<()> {
+    let Deleverage {
+      user,
+      config,
+      market,
+      borrower_shares,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+      quote_token_program,
+      collateral_token_program,
+      ..
+    } = ctx.accounts;
+
+    require!(
+      !market.paused.withdraw_collateral,
+      MarketError::MarketPaused
+    );
+
+    let old_owner_borrow_shares = borrower_shares.borrow_shares;
+    let old_owner_collateral = borrower_shares.collateral_amount;
+    let old_total_borrow_shares = market.total_borrow_shares;
+    let old_total_collateral = market.total_collateral;
+
+    accrue_interest(market, config)?;
+
+    // the position is only checked once the debt is repaid
+    market.total_collateral = market
+      .total_collateral
+      .checked_sub(args.collateral_amount)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    borrower_shares.collateral_amount = borrower_shares
+      .collateral_amount
+      .checked_sub(args.collateral_amount)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    let seeds = generate_market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    transfer_from_vault(
+      collateral_token_program,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      &market.to_account_info(),
+      args.collateral_amount,
+      signer,
+    )?;
+
+    let quote_before = user_ata_quote.amount;
+
+    invoke_callback(
+      ctx.remaining_accounts,
+      "on_deleverage",
+      &DeleverageCallbackArgs {
+        collateral_amount: args.collateral_amount,
+        data: args.data,
+      },
+    )?;
+
+    user_ata_quote.reload()?;
+    vault_ata_quote.reload()?;
+
+    let quote_out = user_ata_quote.amount.saturating_sub(quote_before);
+    require_gte!(quote_out, args.min_quote_out, MarketError::SlippageExceeded);
+
+    // repay up to the whole debt, anything left stays with the user
+    let total_borrows = market.total_borrows()?;
+    let debt = to_assets_up(
+      borrower_shares.borrow_shares,
+      total_borrows,
+      market.total_borrow_shares,
+    )?;
+
+    let (assets, shares) = if quote_out >= amount_with_transfer_fee(quote_mint, debt)? {
+      let received = transfer_to_vault(
+        quote_token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        amount_with_transfer_fee(quote_mint, debt)?,
+      )?;
+      require_gte!(received, debt, MarketError::InsufficientBalance);
+
+      (debt, borrower_shares.borrow_shares)
+    } else {
+      // credit what the vault actually received
+      let received = transfer_to_vault(
+        quote_token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        quote_out,
+      )?;
+
+      (
+        received,
+        to_shares_down(received, total_borrows, market.total_borrow_shares)?,
+      )
+    };
+
+    market.total_borrow_shares = market
+      .total_borrow_shares
+      .checked_sub(shares)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    borrower_shares.borrow_shares = borrower_shares
+      .borrow_shares
+      .checked_sub(shares)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    if !is_solvent(
+      market,
+      &oracle_accounts,
+      borrower_shares.borrow_shares,
+      borrower_shares.collateral_amount,
+      collateral_mint.decimals,
+    )? {
+      return err!(MarketError::NotSolvent);
+    }
+
+    emit!(events::Deleverage {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      collateral_amount: args.collateral_amount,
+      quote_out,
+      repaid_assets: assets,
+      repaid_shares: shares,
+      old_owner_borrow_shares,
+      owner_borrow_shares: borrower_shares.borrow_shares,
+      old_owner_collateral,
+      owner_collateral: borrower_shares.collateral_amount,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+      old_total_collateral,
+      total_collateral: market.total_collateral,
+    });
+
+    Ok(())
+  }
+}
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
 use crate::math::*;
+use crate::transfer::{amount_with_transfer_fee, transfer_to_vault};
 use crate::{accrue_interest::accrue_interest, state::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -32,7 +34,7 @@ pub struct Deposit<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -52,27 +54,32 @@ pub struct Deposit<'info> {
   )]
   pub lender_shares: Box<Account<'info, LenderShares>>,
 
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = user,
+    associated_token::token_program = token_program,
   )]
-  pub user_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -88,12 +95,15 @@ impl<'info> Deposit<'info> {
       config,
       market,
       lender_shares,
+      quote_mint,
       user_ata_quote,
       vault_ata_quote,
       token_program,
       ..
     } = ctx.accounts;
 
+    require!(!market.paused.deposit, MarketError::MarketPaused);
+
     let mut shares = args.shares;
     let mut assets = args.amount;
 
@@ -106,14 +116,37 @@ impl<'info> Deposit<'info> {
 
     msg!("depositing {}", assets);
 
+    let old_owner_shares = lender_shares.shares;
+    let old_total_shares = market.total_shares;
+    let old_deposit_index = market.deposit_index;
+
     accrue_interest(market, config)?;
 
     let total_deposits = market.total_deposits()?;
 
     if assets > 0 {
+      // credit what the vault actually received
+      assets = transfer_to_vault(
+        token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        assets,
+      )?;
       shares = to_shares_down(assets, total_deposits, market.total_shares)?;
     } else {
       assets = to_assets_up(shares, total_deposits, market.total_shares)?;
+
+      let received = transfer_to_vault(
+        token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        amount_with_transfer_fee(quote_mint, assets)?,
+      )?;
+      require_gte!(received, assets, MarketError::InsufficientBalance);
     }
 
     // Update market shares
@@ -128,18 +161,27 @@ impl<'info> Deposit<'info> {
       .checked_add(shares)
       .ok_or(MarketError::MathOverflow)?;
 
-    // Create CpiContext for the transfer
-    let cpi_context = CpiContext::new(
-      token_program.to_account_info(),
-      Transfer {
-        from: user_ata_quote.to_account_info(),
-        to: vault_ata_quote.to_account_info(),
-        authority: user.to_account_info(),
-      },
-    );
-
-    // transfer tokens to vault
-    transfer(cpi_context, assets)?;
+    if market.supply_cap != 0 {
+      require_gte!(
+        market.supply_cap,
+        market.total_deposits()?,
+        MarketError::SupplyCapExceeded
+      );
+    }
+
+    emit!(events::Deposit {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      assets,
+      shares,
+      old_owner_shares,
+      owner_shares: lender_shares.shares,
+      old_total_shares,
+      total_shares: market.total_shares,
+      old_deposit_index,
+      deposit_index: market.deposit_index,
+    });
 
     Ok(())
   }
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
+use crate::transfer::transfer_to_vault;
 use crate::{accrue_interest::accrue_interest, state::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -30,7 +32,7 @@ pub struct DepositCollateral<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -51,26 +53,31 @@ pub struct DepositCollateral<'info> {
   pub borrower_shares: Box<Account<'info, BorrowerShares>>,
 
   #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = collateral_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     mut,
     associated_token::mint = collateral_mint,
     associated_token::authority = user,
+    associated_token::token_program = token_program,
   )]
-  pub user_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -87,16 +94,28 @@ impl<'info> DepositCollateral<'info> {
       config,
       market,
       borrower_shares,
+      collateral_mint,
       user_ata_collateral,
       vault_ata_collateral,
       token_program,
       ..
     } = ctx.accounts;
 
-    let assets = args.amount;
+    let old_owner_collateral = borrower_shares.collateral_amount;
+    let old_total_collateral = market.total_collateral;
 
     accrue_interest(market, config)?;
 
+    // Transfer collateral tokens from user to vault, crediting what was received
+    let assets = transfer_to_vault(
+      token_program,
+      collateral_mint,
+      user_ata_collateral,
+      vault_ata_collateral,
+      &user.to_account_info(),
+      args.amount,
+    )?;
+
     // Update market state
     market.total_collateral = market
       .total_collateral
@@ -111,18 +130,16 @@ impl<'info> DepositCollateral<'info> {
 
     msg!("Depositing {} collateral to the vault", assets);
 
-    // Transfer collateral tokens from user to vault
-    transfer(
-      CpiContext::new(
-        token_program.to_account_info(),
-        Transfer {
-          from: user_ata_collateral.to_account_info(),
-          to: vault_ata_collateral.to_account_info(),
-          authority: user.to_account_info(),
-        },
-      ),
-      assets,
-    )?;
+    emit!(events::DepositCollateral {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      amount: assets,
+      old_owner_collateral,
+      owner_collateral: borrower_shares.collateral_amount,
+      old_total_collateral,
+      total_collateral: market.total_collateral,
+    });
 
     Ok(())
   }
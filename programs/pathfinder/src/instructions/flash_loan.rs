@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+  load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::callback::invoke_callback;
+use crate::error::MarketError;
+use crate::transfer::transfer_from_vault;
+use crate::{generate_market_seeds, state::*};
+
+// position of the market account in both `FlashLoan` and `FlashRepay`
+pub const FLASH_LOAN_MARKET_ACCOUNT_INDEX: usize = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FlashLoanArgs {
+  pub quote_amount: u64,
+  pub collateral_amount: u64,
+  pub data: Vec<u8>,
+}
+
+// data passed to the `on_flash_loan` instruction of the callback program
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FlashLoanCallbackArgs {
+  pub quote_amount: u64,
+  pub collateral_amount: u64,
+  pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: FlashLoanArgs)]
+pub struct FlashLoan<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [
+      MARKET_SEED_PREFIX,
+      quote_mint.key().as_ref(),
+      collateral_mint.key().as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  /// CHECK: address is checked, used to find the matching flash_repay instruction
+  #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+  pub instructions_sysvar: AccountInfo<'info>,
+
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FlashLoan<'info> {
+  pub fn validate(&self, args: &FlashLoanArgs) -> Result<()> {
+    require!(
+      args.quote_amount != 0 || args.collateral_amount != 0,
+      MarketError::InvalidInput
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<'_, '_, '_, 'info, Self>, args: FlashLoanArgs) -> Result<()> {
+    let FlashLoan {
+      market,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      instructions_sysvar,
+      quote_token_program,
+      collateral_token_program,
+      ..
+    } = ctx.accounts;
+
+    // lending quote is a borrow and lending collateral a collateral withdrawal
+    // as far as the guardian is concerned
+    if args.quote_amount > 0 {
+      require!(!market.paused.borrow, MarketError::MarketPaused);
+    }
+    if args.collateral_amount > 0 {
+      require!(
+        !market.paused.withdraw_collateral,
+        MarketError::MarketPaused
+      );
+    }
+
+    // without a callback program the loan must be closed by a flash_repay
+    // instruction later in the same transaction
+    let use_callback = !ctx.remaining_accounts.is_empty();
+    if !use_callback {
+      check_flash_repay(instructions_sysvar, &market.key(), &args)?;
+    }
+
+    let quote_before = vault_ata_quote.amount;
+    let collateral_before = vault_ata_collateral.amount;
+
+    msg!(
+      "flash loan {} quote, {} collateral",
+      args.quote_amount,
+      args.collateral_amount
+    );
+
+    let seeds = generate_market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    if args.quote_amount > 0 {
+      transfer_from_vault(
+        quote_token_program,
+        quote_mint,
+        vault_ata_quote,
+        user_ata_quote,
+        &market.to_account_info(),
+        args.quote_amount,
+        signer,
+      )?;
+    }
+
+    if args.collateral_amount > 0 {
+      transfer_from_vault(
+        collateral_token_program,
+        collateral_mint,
+        vault_ata_collateral,
+        user_ata_collateral,
+        &market.to_account_info(),
+        args.collateral_amount,
+        signer,
+      )?;
+    }
+
+    if use_callback {
+      invoke_callback(
+        ctx.remaining_accounts,
+        "on_flash_loan",
+        &FlashLoanCallbackArgs {
+          quote_amount: args.quote_amount,
+          collateral_amount: args.collateral_amount,
+          data: args.data,
+        },
+      )?;
+
+      vault_ata_quote.reload()?;
+      vault_ata_collateral.reload()?;
+
+      require_gte!(
+        vault_ata_quote.amount,
+        quote_before,
+        MarketError::FlashLoanNotRepaid
+      );
+      require_gte!(
+        vault_ata_collateral.amount,
+        collateral_before,
+        MarketError::FlashLoanNotRepaid
+      );
+    }
+
+    Ok(())
+  }
+}
+
+/// Finds the flash_repay instruction closing this loan. The first flash
+/// instruction for the same market after this one must be a flash_repay for
+/// the same amounts, which rules out nesting loans on a single repayment.
+fn check_flash_repay(
+  instructions_sysvar: &AccountInfo,
+  market: &Pubkey,
+  args: &FlashLoanArgs,
+) -> Result<()> {
+  // the sysvar only describes top level instructions
+  require_eq!(
+    get_stack_height(),
+    TRANSACTION_LEVEL_STACK_HEIGHT,
+    MarketError::InvalidFlashLoan
+  );
+
+  let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+  let mut index = current_index + 1;
+
+  loop {
+    let ix = load_instruction_at_checked(index, instructions_sysvar)
+      .map_err(|_| error!(MarketError::FlashLoanNotRepaid))?;
+    index += 1;
+
+    if ix.program_id != crate::ID || ix.data.len() < 8 {
+      continue;
+    }
+
+    let same_market = ix
+      .accounts
+      .get(FLASH_LOAN_MARKET_ACCOUNT_INDEX)
+      .map_or(false, |meta| meta.pubkey == *market);
+
+    if !same_market {
+      continue;
+    }
+
+    let discriminator = &ix.data[..8];
+
+    if discriminator == crate::instruction::FlashLoan::DISCRIMINATOR {
+      return err!(MarketError::InvalidFlashLoan);
+    }
+
+    if discriminator == crate::instruction::FlashRepay::DISCRIMINATOR {
+      let repay = crate::instruction::FlashRepay::try_from_slice(&ix.data[8..])?;
+
+      require!(
+        repay.args.quote_amount == args.quote_amount
+          && repay.args.collateral_amount == args.collateral_amount,
+        MarketError::InvalidFlashLoan
+      );
+
+      return Ok(());
+    }
+  }
+}
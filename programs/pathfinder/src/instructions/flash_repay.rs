@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::MarketError;
+use crate::transfer::{amount_with_transfer_fee, transfer_to_vault};
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FlashRepayArgs {
+  pub quote_amount: u64,
+  pub collateral_amount: u64,
+}
+
+// account order must keep `market` at FLASH_LOAN_MARKET_ACCOUNT_INDEX
+#[derive(Accounts)]
+#[instruction(args: FlashRepayArgs)]
+pub struct FlashRepay<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [
+      MARKET_SEED_PREFIX,
+      quote_mint.key().as_ref(),
+      collateral_mint.key().as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FlashRepay<'info> {
+  pub fn validate(&self, args: &FlashRepayArgs) -> Result<()> {
+    require!(
+      args.quote_amount != 0 || args.collateral_amount != 0,
+      MarketError::InvalidInput
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: FlashRepayArgs) -> Result<()> {
+    let FlashRepay {
+      user,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      quote_token_program,
+      collateral_token_program,
+      ..
+    } = ctx.accounts;
+
+    msg!(
+      "flash repay {} quote, {} collateral",
+      args.quote_amount,
+      args.collateral_amount
+    );
+
+    // the vaults must get back the full amounts, so transfer fees are on the user
+    if args.quote_amount > 0 {
+      let received = transfer_to_vault(
+        quote_token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        amount_with_transfer_fee(quote_mint, args.quote_amount)?,
+      )?;
+      require_gte!(received, args.quote_amount, MarketError::FlashLoanNotRepaid);
+    }
+
+    if args.collateral_amount > 0 {
+      let received = transfer_to_vault(
+        collateral_token_program,
+        collateral_mint,
+        user_ata_collateral,
+        vault_ata_collateral,
+        &user.to_account_info(),
+        amount_with_transfer_fee(collateral_mint, args.collateral_amount)?,
+      )?;
+      require_gte!(
+        received,
+        args.collateral_amount,
+        MarketError::FlashLoanNotRepaid
+      );
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::interest_rate::validate_curve_limits;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitializeConfigArgs {
+  pub authority: Pubkey,
+  pub fee_recipient: Pubkey,
+  pub curve_limits: AdaptiveCurveLimits,
+}
+
+// Creates the protocol config once, every admin instruction requires it afterwards.
+// Only the program upgrade authority may do so, so a deployment cannot be front-run.
+#[derive(Accounts)]
+#[instruction(args: InitializeConfigArgs)]
+pub struct InitializeConfig<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [crate::ID.as_ref()],
+    bump,
+    seeds::program = bpf_loader_upgradeable::ID,
+    constraint = program_data.upgrade_authority_address == Some(user.key()) @ MarketError::InvalidAuthority,
+  )]
+  pub program_data: Box<Account<'info, ProgramData>>,
+
+  #[account(
+    init,
+    payer = user,
+    space = 8 + Config::INIT_SPACE,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+  pub fn validate(&self, args: &InitializeConfigArgs) -> Result<()> {
+    require!(
+      args.authority != Pubkey::default(),
+      MarketError::InvalidAuthority
+    );
+    require!(
+      args.fee_recipient != Pubkey::default(),
+      MarketError::InvalidRecipient
+    );
+    validate_curve_limits(&args.curve_limits)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: InitializeConfigArgs) -> Result<()> {
+    let InitializeConfig { config, .. } = ctx.accounts;
+
+    config.set_inner(Config {
+      bump: ctx.bumps.config,
+      version: CONFIG_VERSION,
+      authority: args.authority,
+      pending_authority: Pubkey::default(),
+      guardian: Pubkey::default(),
+      fee_factor: 0,
+      liquidation_fee_factor: 0,
+      fee_recipient: args.fee_recipient,
+      curve_limits: args.curve_limits,
+      reserved: [0; CONFIG_RESERVED_BYTES],
+    });
+
+    emit!(events::InitializeConfig {
+      authority: args.authority,
+      fee_recipient: args.fee_recipient,
+      curve_limits: args.curve_limits,
+    });
+
+    Ok(())
+  }
+}
@@ -3,19 +3,19 @@ use crate::math::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
-/// Curve steepness (scaled by WAD).
+/// Default curve steepness (scaled by WAD).
 /// Curve steepness = 4.
 pub const CURVE_STEEPNESS: i128 = 4 * WAD_INT;
 
 pub const YEAR_SECONDS: i128 = 365 * 24 * 60 * 60;
 
-/// Adjustment speed per second (scaled by WAD).
-/// The speed is per second, so the rate moves at a speed of ADJUSTMENT_SPEED * err each second
+/// Default adjustment speed per second (scaled by WAD).
+/// The speed is per second, so the rate moves at a speed of adjustment speed * err each second
 /// (while being continuously compounded).
 /// Adjustment speed = 50/year.
 pub const ADJUSTMENT_SPEED: i128 = 50 * WAD_INT / YEAR_SECONDS;
 
-/// Target utilization (scaled by WAD).
+/// Default target utilization (scaled by WAD).
 /// Target utilization = 90%.
 pub const TARGET_UTILIZATION: i128 = 9 * WAD_INT / 10;
 
@@ -23,37 +23,169 @@ pub const TARGET_UTILIZATION: i128 = 9 * WAD_INT / 10;
 /// Initial rate at target = 4% (rate between 1% and 16%).
 pub const INITIAL_RATE_AT_TARGET: i128 = 4 * WAD_INT / 100 / YEAR_SECONDS;
 
-/// Minimum rate at target per second (scaled by WAD).
+/// Default minimum rate at target per second (scaled by WAD).
 /// Minimum rate at target = 0.1% (minimum rate = 0.025%).
 pub const MIN_RATE_AT_TARGET: i128 = WAD_INT / 1000 / YEAR_SECONDS;
 
-/// Maximum rate at target per second (scaled by WAD).
+/// Default maximum rate at target per second (scaled by WAD).
 /// Maximum rate at target = 200% (maximum rate = 800%).
 pub const MAX_RATE_AT_TARGET: i128 = 2 * WAD_INT / YEAR_SECONDS;
 
-pub fn get_rate(market: &Account<Market>) -> Result<(Decimal, Decimal)> {
+/// Maximum borrow rate per second (scaled by WAD) of the fixed and kink models.
+/// Maximum rate = 800%, the highest rate the adaptive curve can reach.
+pub const MAX_BORROW_RATE: i128 = CURVE_STEEPNESS * MAX_RATE_AT_TARGET / WAD_INT;
+
+/// Returns the average borrow rate since the last accrual and the new rate at target.
+/// Models without a rate at target leave `market.rate_at_target` unchanged.
+pub fn get_rate(market: &Market) -> Result<(Decimal, Decimal)> {
+  let rate_at_target = Decimal::from_raw_u128(market.rate_at_target);
+
+  match market.irm {
+    IrmKind::Adaptive { curve } => adaptive_rate(market, &curve),
+    IrmKind::Fixed { rate } => Ok((Decimal::from_raw_u64(rate), rate_at_target)),
+    IrmKind::LinearKink {
+      base_rate,
+      slope_low,
+      slope_high,
+      kink_utilization,
+    } => {
+      let rate = linear_kink_rate(
+        utilization(market)?,
+        base_rate as i128,
+        slope_low as i128,
+        slope_high as i128,
+        kink_utilization as i128,
+      )?;
+      Ok((Decimal::from_raw_i128(rate), rate_at_target))
+    }
+  }
+}
+
+/// Checks the parameters of an interest rate model chosen at market creation.
+/// Adaptive curves must fall within the protocol `limits`.
+pub fn validate_irm(irm: &IrmKind, limits: &AdaptiveCurveLimits) -> Result<()> {
+  match *irm {
+    IrmKind::Adaptive { curve } => {
+      require!(
+        curve.target_utilization >= limits.min_target_utilization
+          && curve.target_utilization <= limits.max_target_utilization,
+        MarketError::InvalidIrm
+      );
+      require!(
+        curve.curve_steepness as i128 >= WAD_INT
+          && curve.curve_steepness <= limits.max_curve_steepness,
+        MarketError::InvalidIrm
+      );
+      require!(
+        curve.adjustment_speed <= limits.max_adjustment_speed,
+        MarketError::InvalidIrm
+      );
+      require!(
+        curve.min_rate_at_target >= limits.min_rate_at_target
+          && curve.min_rate_at_target <= curve.max_rate_at_target
+          && curve.max_rate_at_target <= limits.max_rate_at_target,
+        MarketError::InvalidIrm
+      );
+    }
+    IrmKind::Fixed { rate } => {
+      require!(rate as i128 <= MAX_BORROW_RATE, MarketError::InvalidIrm);
+    }
+    IrmKind::LinearKink {
+      base_rate,
+      slope_low,
+      slope_high,
+      kink_utilization,
+    } => {
+      require!(
+        kink_utilization > 0 && (kink_utilization as i128) < WAD_INT,
+        MarketError::InvalidIrm
+      );
+
+      let max_rate = (base_rate as i128)
+        .checked_add(slope_low as i128)
+        .and_then(|rate| rate.checked_add(slope_high as i128))
+        .ok_or(MarketError::MathOverflow)?;
+      require!(max_rate <= MAX_BORROW_RATE, MarketError::InvalidIrm);
+    }
+  }
+
+  Ok(())
+}
+
+/// Checks the protocol limits on adaptive curves set by the authority.
+pub fn validate_curve_limits(limits: &AdaptiveCurveLimits) -> Result<()> {
+  require!(
+    limits.min_target_utilization > 0
+      && limits.min_target_utilization <= limits.max_target_utilization
+      && (limits.max_target_utilization as i128) < WAD_INT,
+    MarketError::InvalidIrm
+  );
+  require!(
+    limits.max_curve_steepness as i128 >= WAD_INT,
+    MarketError::InvalidIrm
+  );
+  require!(
+    limits.min_rate_at_target > 0 && limits.min_rate_at_target <= limits.max_rate_at_target,
+    MarketError::InvalidIrm
+  );
+
+  Ok(())
+}
+
+/// Returns the utilization of the market (scaled by WAD).
+pub fn utilization(market: &Market) -> Result<i128> {
   let total_deposits = market.total_deposits()?;
   let total_borrows = market.total_borrows()?;
 
   // Safe "unchecked" cast because the utilization is smaller than 1 (scaled by WAD).
-  let utilization: i128 = if total_deposits > 0 {
+  Ok(if total_deposits > 0 {
     w_div_down(total_borrows, total_deposits)? as i128
   } else {
     0
-  };
+  })
+}
+
+/// @dev Returns the rate of a two-slope model for a given `utilization`.
+/// The formula is the following:
+/// r = baseRate + slopeLow * u / kink                                  if u <= kink
+///     baseRate + slopeLow + slopeHigh * (u - kink) / (1 - kink)      else.
+pub fn linear_kink_rate(
+  utilization: i128,
+  base_rate: i128,
+  slope_low: i128,
+  slope_high: i128,
+  kink_utilization: i128,
+) -> Result<i128> {
+  let utilization = bound(utilization, 0, WAD_INT)?;
+
+  if utilization <= kink_utilization {
+    let low = w_mul_to_zero(slope_low, w_div_to_zero(utilization, kink_utilization)?)?;
+    return Ok(base_rate + low);
+  }
+
+  let excess = w_div_to_zero(utilization - kink_utilization, WAD_INT - kink_utilization)?;
+  let high = w_mul_to_zero(slope_high, excess)?;
+
+  Ok(base_rate + slope_low + high)
+}
+
+/// Returns the average rate and the new rate at target of the adaptive curve.
+fn adaptive_rate(market: &Market, curve: &AdaptiveCurve) -> Result<(Decimal, Decimal)> {
+  let utilization = utilization(market)?;
+  let target_utilization = curve.target_utilization as i128;
 
   // The normalization factor is used to scale the error and helps in adjusting the interest rate
   // in a way that is proportional to how far the current utilization is from the target utilization.
   // max value is 0.999999999999999999 * 1e18
-  let err_norm_factor: i128 = if utilization > TARGET_UTILIZATION {
-    WAD_INT - TARGET_UTILIZATION
+  let err_norm_factor: i128 = if utilization > target_utilization {
+    WAD_INT - target_utilization
   } else {
-    TARGET_UTILIZATION
+    target_utilization
   };
 
   // The error is the difference between the current utilization and the target utilization,
   // in mul max value is 0.999999999999999999 * 1e18 * 0.999999999999999999 * 1e18
-  let err = w_div_to_zero(utilization - TARGET_UTILIZATION, err_norm_factor)?;
+  let err = w_div_to_zero(utilization - target_utilization, err_norm_factor)?;
 
   let start_rate_at_target: i128 = market.rate_at_target as i128;
 
@@ -62,14 +194,19 @@ pub fn get_rate(market: &Account<Market>) -> Result<(Decimal, Decimal)> {
 
   if start_rate_at_target == 0 {
     // First interaction.
-    avg_rate_at_target = INITIAL_RATE_AT_TARGET;
-    end_rate_at_target = INITIAL_RATE_AT_TARGET;
+    let initial_rate_at_target = bound(
+      INITIAL_RATE_AT_TARGET,
+      curve.min_rate_at_target as i128,
+      curve.max_rate_at_target as i128,
+    )?;
+    avg_rate_at_target = initial_rate_at_target;
+    end_rate_at_target = initial_rate_at_target;
   } else {
     // The speed is assumed constant between two updates, but it is in fact not constant because of interest.
     // So the rate is always underestimated.
     // rate of change (how quickly the interest rate should adjust)
     // max value is 1.58... * 1e12 * 0.999999999999999999 * 1e18
-    let speed: i128 = w_mul_to_zero(ADJUSTMENT_SPEED, err)?;
+    let speed: i128 = w_mul_to_zero(curve.adjustment_speed as i128, err)?;
 
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp as u64;
@@ -99,13 +236,13 @@ pub fn get_rate(market: &Account<Market>) -> Result<(Decimal, Decimal)> {
       // With N = 2:
       // avg ~= curve([(startRateAtTarget + endRateAtTarget)/2 + startRateAtTarget*exp(speed*T/2)] / 2, err)
       // avg ~= curve([startRateAtTarget + endRateAtTarget + 2*startRateAtTarget*exp(speed*T/2)] / 4, err)
-      end_rate_at_target = _new_rate_at_target(start_rate_at_target, linear_adaptation)?;
+      end_rate_at_target = _new_rate_at_target(start_rate_at_target, linear_adaptation, curve)?;
 
       let mid_linear = linear_adaptation
         .checked_div(2)
         .ok_or(MarketError::MathOverflow)?;
 
-      let mid_rate_at_target = _new_rate_at_target(start_rate_at_target, mid_linear)?;
+      let mid_rate_at_target = _new_rate_at_target(start_rate_at_target, mid_linear, curve)?;
 
       let weighted_sum = start_rate_at_target + end_rate_at_target + 2 * mid_rate_at_target;
 
@@ -117,7 +254,7 @@ pub fn get_rate(market: &Account<Market>) -> Result<(Decimal, Decimal)> {
 
   // Safe "unchecked" cast because avgRateAtTarget >= 0.
   Ok((
-    Decimal::from_raw_i128(_curve(avg_rate_at_target, err)?),
+    Decimal::from_raw_i128(_curve(avg_rate_at_target, err, curve.curve_steepness as i128)?),
     Decimal::from_raw_i128(end_rate_at_target),
   ))
 }
@@ -126,12 +263,12 @@ pub fn get_rate(market: &Account<Market>) -> Result<(Decimal, Decimal)> {
 /// The formula of the curve is the following:
 /// r = ((1-1/C)*err + 1) * rateAtTarget if err < 0
 ///     ((C-1)*err + 1) * rateAtTarget else.
-pub fn _curve(rate_at_target: i128, err: i128) -> Result<i128> {
+pub fn _curve(rate_at_target: i128, err: i128, curve_steepness: i128) -> Result<i128> {
   // Non negative because 1 - 1/C >= 0, C - 1 >= 0.
   let coeff = if err < 0 {
-    WAD_INT - w_div_to_zero(WAD_INT, CURVE_STEEPNESS)?
+    WAD_INT - w_div_to_zero(WAD_INT, curve_steepness)?
   } else {
-    CURVE_STEEPNESS - WAD_INT
+    curve_steepness - WAD_INT
   };
 
   // Non negative if _rateAtTarget >= 0 because if err < 0, coeff <= 1.
@@ -142,9 +279,17 @@ pub fn _curve(rate_at_target: i128, err: i128) -> Result<i128> {
 
 /// @dev Returns the new rate at target, for a given `startRateAtTarget` and a given `linearAdaptation`.
 /// The formula is: max(min(startRateAtTarget * exp(linearAdaptation), maxRateAtTarget), minRateAtTarget).
-pub fn _new_rate_at_target(start_rate_at_target: i128, linear_adaptation: i128) -> Result<i128> {
-  // Non negative because MIN_RATE_AT_TARGET > 0.
+pub fn _new_rate_at_target(
+  start_rate_at_target: i128,
+  linear_adaptation: i128,
+  curve: &AdaptiveCurve,
+) -> Result<i128> {
+  // Non negative because the minimum rate at target is positive.
   let result = w_mul_to_zero(start_rate_at_target, w_exp(linear_adaptation)?)?;
 
-  bound(result, MIN_RATE_AT_TARGET, MAX_RATE_AT_TARGET)
+  bound(
+    result,
+    curve.min_rate_at_target as i128,
+    curve.max_rate_at_target as i128,
+  )
 }
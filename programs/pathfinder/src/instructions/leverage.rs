@@ -0,0 +1,321 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::callback::invoke_callback;
+use crate::error::MarketError;
+use crate::events;
+use crate::math::*;
+use crate::oracle::{oracle_get_price, OracleAccounts};
+use crate::transfer::{transfer_from_vault, transfer_to_vault};
+use crate::{
+  accrue_interest::accrue_interest,
+  borrow::{is_solvent, max_debt},
+  generate_market_seeds,
+  state::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LeverageArgs {
+  // own collateral added to the position, may be zero
+  pub collateral_amount: u64,
+  // quote borrowed and handed to the swap
+  pub quote_amount: u64,
+  // least collateral the swap must return
+  pub min_collateral_out: u64,
+  // highest ltv the position may end at, scaled by LTV_PRECISION
+  pub max_ltv: u64,
+  pub data: Vec<u8>,
+}
+
+// data passed to the `on_leverage` instruction of the swap program
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LeverageCallbackArgs {
+  pub quote_amount: u64,
+  pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: LeverageArgs)]
+pub struct Leverage<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      quote_mint.key().as_ref(),
+      collateral_mint.key().as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    space = 8 + std::mem::size_of::<BorrowerShares>(),
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Box<Account<'info, BorrowerShares>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint,
+    associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+  /// CHECK: needed for dynamic oracle account
+  pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
+
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> Leverage<'info> {
+  pub fn validate(&self, args: &LeverageArgs) -> Result<()> {
+    require!(
+      args.quote_amount != 0 && args.max_ltv as u128 <= LTV_PRECISION,
+      MarketError::InvalidLeverageInput
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<'_, '_, '_, 'info, Self>, args: LeverageArgs) -> Result<()> {
+    let Leverage {
+      user,
+      config,
+      market,
+      borrower_shares,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+      quote_token_program,
+      collateral_token_program,
+      ..
+    } = ctx.accounts;
+
+    require!(!market.paused.borrow, MarketError::MarketPaused);
+
+    let old_owner_borrow_shares = borrower_shares.borrow_shares;
+    let old_owner_collateral = borrower_shares.collateral_amount;
+    let old_total_borrow_shares = market.total_borrow_shares;
+    let old_total_collateral = market.total_collateral;
+
+    accrue_interest(market, config)?;
+
+    let mut deposited_collateral = 0;
+    if args.collateral_amount > 0 {
+      // credit what the vault actually received
+      deposited_collateral = transfer_to_vault(
+        collateral_token_program,
+        collateral_mint,
+        user_ata_collateral,
+        vault_ata_collateral,
+        &user.to_account_info(),
+        args.collateral_amount,
+      )?;
+    }
+
+    // the debt is recorded up front, the position is only checked once the swap is deposited
+    let shares = to_shares_up(
+      args.quote_amount,
+      market.total_borrows()?,
+      market.total_borrow_shares,
+    )?;
+
+    market.total_borrow_shares = market
+      .total_borrow_shares
+      .checked_add(shares)
+      .ok_or(MarketError::MathOverflow)?;
+
+    borrower_shares.borrow_shares = borrower_shares
+      .borrow_shares
+      .checked_add(shares)
+      .ok_or(MarketError::MathOverflow)?;
+
+    if market.borrow_cap != 0 {
+      require_gte!(
+        market.borrow_cap,
+        market.total_borrows()?,
+        MarketError::BorrowCapExceeded
+      );
+    }
+
+    let seeds = generate_market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    transfer_from_vault(
+      quote_token_program,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      &market.to_account_info(),
+      args.quote_amount,
+      signer,
+    )?;
+
+    user_ata_collateral.reload()?;
+    let collateral_before = user_ata_collateral.amount;
+
+    invoke_callback(
+      ctx.remaining_accounts,
+      "on_leverage",
+      &LeverageCallbackArgs {
+        quote_amount: args.quote_amount,
+        data: args.data,
+      },
+    )?;
+
+    user_ata_collateral.reload()?;
+    vault_ata_collateral.reload()?;
+
+    let collateral_out = user_ata_collateral.amount.saturating_sub(collateral_before);
+    require_gte!(
+      collateral_out,
+      args.min_collateral_out,
+      MarketError::SlippageExceeded
+    );
+
+    let swapped_collateral = transfer_to_vault(
+      collateral_token_program,
+      collateral_mint,
+      user_ata_collateral,
+      vault_ata_collateral,
+      &user.to_account_info(),
+      collateral_out,
+    )?;
+
+    let added_collateral = deposited_collateral
+      .checked_add(swapped_collateral)
+      .ok_or(MarketError::MathOverflow)?;
+
+    market.total_collateral = market
+      .total_collateral
+      .checked_add(added_collateral)
+      .ok_or(MarketError::MathOverflow)?;
+
+    borrower_shares.collateral_amount = borrower_shares
+      .collateral_amount
+      .checked_add(added_collateral)
+      .ok_or(MarketError::MathOverflow)?;
+
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    if !is_solvent(
+      market,
+      &oracle_accounts,
+      borrower_shares.borrow_shares,
+      borrower_shares.collateral_amount,
+      collateral_mint.decimals,
+    )? {
+      return err!(MarketError::NotSolvent);
+    }
+
+    // user bound on the resulting ltv, priced like the solvency check
+    let price = oracle_get_price(&market.oracle, &oracle_accounts, false)?;
+    let collateral_value = collateral_to_quote_down(
+      borrower_shares.collateral_amount,
+      &price,
+      collateral_mint.decimals,
+      quote_mint.decimals,
+    )?;
+    let borrowed = to_assets_up(
+      borrower_shares.borrow_shares,
+      market.total_borrows()?,
+      market.total_borrow_shares,
+    )?;
+    require!(
+      borrowed as u128 <= max_debt(collateral_value, args.max_ltv)?,
+      MarketError::LeverageLtvExceeded
+    );
+
+    emit!(events::Leverage {
+      market: market.key(),
+      owner: user.key(),
+      deposited_collateral,
+      borrowed_assets: args.quote_amount,
+      borrowed_shares: shares,
+      swapped_collateral,
+      old_owner_borrow_shares,
+      owner_borrow_shares: borrower_shares.borrow_shares,
+      old_owner_collateral,
+      owner_collateral: borrower_shares.collateral_amount,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+      old_total_collateral,
+      total_collateral: market.total_collateral,
+    });
+
+    Ok(())
+  }
+}
@@ -1,18 +1,32 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+use crate::callback::invoke_callback;
 use crate::error::MarketError;
+use crate::events::{self, BadDebtRealized};
 use crate::generate_market_seeds;
 use crate::math::*;
-use crate::oracle::oracle_get_price;
-use crate::{accrue_interest::accrue_interest, borrow::is_solvent, state::*};
+use crate::oracle::{oracle_get_price, OracleAccounts};
+use crate::transfer::{amount_with_transfer_fee, transfer_from_vault, transfer_to_vault};
+use crate::views::position_health;
+use crate::{accrue_interest::accrue_interest, borrow::is_liquidatable, state::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct LiquidateArgs {
   pub borrower: Pubkey,
   pub collateral_amount: u64,
   pub repay_shares: u64,
+  pub data: Vec<u8>,
+}
+
+// data passed to the `on_liquidate` instruction of the callback program
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LiquidateCallbackArgs {
+  // quote owed by the liquidator, including the protocol liquidation fee
+  pub repaid_quote: u64,
+  pub collateral_amount: u64,
+  pub data: Vec<u8>,
 }
 
 #[derive(Accounts)]
@@ -34,7 +48,7 @@ pub struct Liquidate<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -52,43 +66,70 @@ pub struct Liquidate<'info> {
   )]
   pub borrower_shares: Box<Account<'info, BorrowerShares>>,
 
-  #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  // required while the market runs liquidation auctions
+  #[account(
+    mut,
+    seeds = [
+      LIQUIDATION_STATE_SEED_PREFIX,
+      market.key().as_ref(),
+      args.borrower.as_ref()
+    ],
+    bump
+  )]
+  pub liquidation_state: Option<Box<Account<'info, LiquidationState>>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.collateral_mint,
     associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
   )]
-  pub vault_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     mut,
     associated_token::mint = collateral_mint,
     associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
   )]
-  pub user_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
   )]
-  pub user_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
   /// CHECK: needed for dynamic oracle account
   pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
 
-  pub token_program: Program<'info, Token>,
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -98,22 +139,29 @@ impl<'info> Liquidate<'info> {
     Ok(())
   }
 
-  pub fn handle(ctx: Context<Self>, args: LiquidateArgs) -> Result<()> {
+  pub fn handle(ctx: Context<'_, '_, '_, 'info, Self>, args: LiquidateArgs) -> Result<()> {
     let Liquidate {
       user,
       config,
       market,
       borrower_shares,
+      liquidation_state,
       collateral_mint,
       vault_ata_collateral,
       user_ata_collateral,
+      quote_mint,
       vault_ata_quote,
       user_ata_quote,
       oracle_ai,
-      token_program,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+      quote_token_program,
+      collateral_token_program,
       ..
     } = ctx.accounts;
 
+    require!(!market.paused.liquidate, MarketError::MarketPaused);
+
     let mut repay_shares = args.repay_shares;
     let mut collateral_amount = args.collateral_amount;
 
@@ -124,11 +172,21 @@ impl<'info> Liquidate<'info> {
       return err!(MarketError::AssetShareValueMismatch);
     }
 
+    let old_borrower_borrow_shares = borrower_shares.borrow_shares;
+    let old_borrower_collateral = borrower_shares.collateral_amount;
+    let old_total_borrow_shares = market.total_borrow_shares;
+
     accrue_interest(market, config)?;
 
-    if is_solvent(
-      market,
+    let oracle_accounts = OracleAccounts {
       oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    if !is_liquidatable(
+      market,
+      &oracle_accounts,
       borrower_shares.borrow_shares,
       borrower_shares.collateral_amount,
       collateral_mint.decimals,
@@ -136,25 +194,56 @@ impl<'info> Liquidate<'info> {
       return err!(MarketError::BorrowerIsSolvent);
     }
 
+    // liquidation ltv in WAD
+    let lltv = Decimal::from_raw_u128(
+      (market.effective_liquidation_ltv() as u128)
+        .checked_mul(WAD / LTV_PRECISION)
+        .ok_or(MarketError::MathOverflow)?,
+    );
+
     let cursor_factor = Decimal::one()
       .try_sub(Decimal::from_raw_u64(LIQUIDATION_CURSOR))?
-      .w_mul_down(Decimal::one().try_sub(Decimal::from_raw_u64(market.ltv_factor))?)?;
+      .w_mul_down(Decimal::one().try_sub(lltv)?)?;
 
     // The liquidation incentive factor is min(maxLiquidationIncentiveFactor, 1/(1 - cursor*(1 - lltv))).
-    let liquidation_incentive_factor = Decimal::min(
+    let max_incentive_factor = Decimal::min(
       Decimal::from_raw_u64(MAX_LIQUIDATION_INCENTIVE_FACTOR),
       Decimal::one().w_div_down(cursor_factor)?,
     );
 
-    let colalteral_price = oracle_get_price(&market.oracle, &oracle_ai, true)?;
+    // a record from before the borrower last touched the position does not count
+    let auction_record = liquidation_state
+      .as_ref()
+      .filter(|state| state.is_current(borrower_shares))
+      .map(|state| state.insolvent_since);
+
+    let liquidation_incentive_factor = match market.liquidation_auction_duration {
+      0 => max_incentive_factor,
+      duration => {
+        let insolvent_since = match auction_record {
+          Some(insolvent_since) => insolvent_since,
+          None => return err!(MarketError::LiquidationAuctionNotStarted),
+        };
+
+        auction_incentive_factor(
+          max_incentive_factor,
+          insolvent_since,
+          Clock::get()?.unix_timestamp as u64,
+          duration,
+        )?
+      }
+    };
+
+    let colalteral_price = oracle_get_price(&market.oracle, &oracle_accounts, true)?;
 
     let total_borrows = market.total_borrows()?;
 
     if collateral_amount > 0 {
-      let collateral_quoted = mul_div_up(
-        collateral_amount as u128,
-        colalteral_price.price as u128,
-        colalteral_price.scale as u128,
+      let collateral_quoted = collateral_to_quote_up(
+        collateral_amount,
+        &colalteral_price,
+        collateral_mint.decimals,
+        quote_mint.decimals,
       )?;
 
       repay_shares = to_shares_up(
@@ -170,22 +259,73 @@ impl<'info> Liquidate<'info> {
       .w_mul_down(liquidation_incentive_factor)?
       .to_u64()?;
 
-      collateral_amount = mul_div_down(
-        collateral_with_incentive as u128,
-        colalteral_price.scale as u128,
-        colalteral_price.price as u128,
+      collateral_amount = quote_to_collateral_down(
+        collateral_with_incentive,
+        &colalteral_price,
+        collateral_mint.decimals,
+        quote_mint.decimals,
       )?;
     }
 
-    let repaid_quote = to_assets_up(repay_shares, total_borrows, market.total_borrow_shares)?;
+    // close factor while the position is only mildly unhealthy
+    let limits = market.liquidation_limits;
+    let mut max_repay_shares = borrower_shares.borrow_shares;
+    if limits.close_factor != 0 {
+      let price = oracle_get_price(&market.oracle, &oracle_accounts, false)?;
+      let collateral_value = collateral_to_quote_down(
+        borrower_shares.collateral_amount,
+        &price,
+        collateral_mint.decimals,
+        quote_mint.decimals,
+      )?;
+      let borrowed = to_assets_up(
+        borrower_shares.borrow_shares,
+        total_borrows,
+        market.total_borrow_shares,
+      )?;
+
+      if position_health(collateral_value, borrowed, market.effective_liquidation_ltv())?
+        > limits.full_close_health
+      {
+        max_repay_shares = mul_div_down(
+          borrower_shares.borrow_shares as u128,
+          limits.close_factor as u128,
+          WAD,
+        )?;
+      }
+    }
+
+    // a capped liquidation that would leave dust may close the whole position
+    let debt_after_max_repay = to_assets_up(
+      borrower_shares.borrow_shares - max_repay_shares,
+      total_borrows,
+      market.total_borrow_shares,
+    )?;
+    if debt_after_max_repay < limits.min_remaining_debt {
+      max_repay_shares = borrower_shares.borrow_shares;
+    }
 
-    // Verify liquidator has sufficient quote tokens
     require_gte!(
-      user_ata_quote.amount,
-      repaid_quote,
-      MarketError::InsufficientBalance
+      max_repay_shares,
+      repay_shares,
+      MarketError::LiquidationLimitExceeded
     );
 
+    // without collateral left the remaining debt is realized as bad debt below
+    if collateral_amount < borrower_shares.collateral_amount {
+      let remaining_debt = to_assets_up(
+        borrower_shares.borrow_shares - repay_shares,
+        total_borrows,
+        market.total_borrow_shares,
+      )?;
+      require!(
+        remaining_debt == 0 || remaining_debt >= limits.min_remaining_debt,
+        MarketError::LiquidationLimitExceeded
+      );
+    }
+
+    let repaid_quote = to_assets_up(repay_shares, total_borrows, market.total_borrow_shares)?;
+
     borrower_shares.borrow_shares = borrower_shares
       .borrow_shares
       .checked_sub(repay_shares)
@@ -201,49 +341,163 @@ impl<'info> Liquidate<'info> {
       .checked_sub(collateral_amount)
       .ok_or(MarketError::MathUnderflow)?;
 
-    if borrower_shares.collateral_amount == 0 {
-      let bad_debt_shares = borrower_shares.borrow_shares;
-      market.total_borrow_shares = market
-        .total_borrow_shares
-        .checked_sub(bad_debt_shares)
-        .unwrap();
+    // debt left without collateral is socialized among lenders
+    let mut bad_debt_shares = 0;
+    let mut bad_debt = 0;
+    if borrower_shares.collateral_amount == 0 && borrower_shares.borrow_shares > 0 {
+      let old_deposit_index = market.deposit_index;
+      bad_debt_shares = borrower_shares.borrow_shares;
+      bad_debt = market.realize_bad_debt(bad_debt_shares)?;
       borrower_shares.borrow_shares = 0;
+
+      emit!(BadDebtRealized {
+        market: market.key(),
+        borrower: args.borrower,
+        bad_debt_shares,
+        bad_debt,
+        old_deposit_index,
+        deposit_index: market.deposit_index,
+      });
+    }
+
+    // protocol cut of the incentive, paid by the liquidator on top of the repaid debt.
+    // Shares of a wiped out market are worth nothing, so no fee is charged there
+    let liquidation_fee = if market.is_wiped_out() {
+      0
+    } else {
+      Decimal::from_raw_u64(repaid_quote)
+        .w_mul_down(liquidation_incentive_factor.try_sub(Decimal::one())?)?
+        .w_mul_down(Decimal::from_raw_u64(config.liquidation_fee_factor))?
+        .to_u64()?
+    };
+    let owed_quote = repaid_quote
+      .checked_add(liquidation_fee)
+      .ok_or(MarketError::MathOverflow)?;
+
+    // the liquidator covers any quote transfer fee
+    let repaid_quote_gross = amount_with_transfer_fee(quote_mint, owed_quote)?;
+
+    // with a callback the liquidator may source the quote tokens from the seized collateral
+    let use_callback = !ctx.remaining_accounts.is_empty();
+
+    // Verify liquidator has sufficient quote tokens
+    if !use_callback {
+      require_gte!(
+        user_ata_quote.amount,
+        repaid_quote_gross,
+        MarketError::InsufficientBalance
+      );
     }
 
-    //add callback mechansim?
+    // the fee is deposited for the fee recipient, like the interest fee
+    let mut fee_shares = 0;
+    if liquidation_fee > 0 {
+      fee_shares = to_shares_down(liquidation_fee, market.total_deposits()?, market.total_shares)?;
+
+      market.fee_shares = market
+        .fee_shares
+        .checked_add(fee_shares)
+        .ok_or(MarketError::MathOverflow)?;
+
+      market.total_shares = market
+        .total_shares
+        .checked_add(fee_shares)
+        .ok_or(MarketError::MathOverflow)?;
+    }
 
     // transfer tokens to liquidator
     let seeds = generate_market_seeds!(market);
     let signer = &[&seeds[..]];
 
-    transfer(
-      CpiContext::new_with_signer(
-        token_program.to_account_info(),
-        Transfer {
-          from: vault_ata_collateral.to_account_info(),
-          to: user_ata_collateral.to_account_info(),
-          authority: market.to_account_info(),
-        },
-        signer,
-      ),
+    transfer_from_vault(
+      collateral_token_program,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      &market.to_account_info(),
       collateral_amount,
+      signer,
     )?;
 
     msg!("Liquidating {} from vault", collateral_amount);
 
-    // Create CpiContext for the transfer
-    let cpi_context = CpiContext::new(
-      token_program.to_account_info(),
-      Transfer {
-        from: user_ata_quote.to_account_info(),
-        to: vault_ata_quote.to_account_info(),
-        authority: user.to_account_info(),
-      },
-    );
+    if use_callback {
+      invoke_callback(
+        ctx.remaining_accounts,
+        "on_liquidate",
+        &LiquidateCallbackArgs {
+          repaid_quote: owed_quote,
+          collateral_amount,
+          data: args.data,
+        },
+      )?;
+
+      user_ata_quote.reload()?;
+      vault_ata_quote.reload()?;
+
+      require_gte!(
+        user_ata_quote.amount,
+        repaid_quote_gross,
+        MarketError::InsufficientBalance
+      );
+    }
 
     // transfer tokens to vault
-    transfer(cpi_context, repaid_quote)?;
+    let received = transfer_to_vault(
+      quote_token_program,
+      quote_mint,
+      user_ata_quote,
+      vault_ata_quote,
+      &user.to_account_info(),
+      repaid_quote_gross,
+    )?;
+    require_gte!(received, owed_quote, MarketError::InsufficientBalance);
+
+    // a closed position starts the next auction from scratch, a partial liquidation keeps it running
+    if let Some(liquidation_state) = liquidation_state {
+      if borrower_shares.borrow_shares == 0 {
+        liquidation_state.clear();
+      } else if let Some(insolvent_since) = auction_record {
+        liquidation_state.record(insolvent_since, borrower_shares);
+      }
+    }
+
+    emit!(events::Liquidate {
+      market: market.key(),
+      liquidator: user.key(),
+      borrower: args.borrower,
+      repaid_quote,
+      repaid_shares: repay_shares,
+      seized_collateral: collateral_amount,
+      incentive_factor: liquidation_incentive_factor.to_u128()?,
+      liquidation_fee,
+      fee_shares,
+      bad_debt,
+      bad_debt_shares,
+      old_borrower_borrow_shares,
+      borrower_borrow_shares: borrower_shares.borrow_shares,
+      old_borrower_collateral,
+      borrower_collateral: borrower_shares.collateral_amount,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+    });
 
     Ok(())
   }
 }
+
+/// Incentive of an auction liquidation, the bonus over one grows linearly from zero
+/// when the position became liquidatable to the full `max_incentive_factor` after `duration`.
+pub fn auction_incentive_factor(
+  max_incentive_factor: Decimal,
+  insolvent_since: u64,
+  now: u64,
+  duration: u64,
+) -> Result<Decimal> {
+  let elapsed = min_u64(now.saturating_sub(insolvent_since), duration);
+
+  Decimal::one().try_add(max_incentive_factor.try_sub(Decimal::one())?.mul_div_down(
+    Decimal::from_raw_u64(elapsed),
+    Decimal::from_raw_u64(duration),
+  )?)
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::interest_rate::validate_curve_limits;
+use crate::migration::{load_legacy, write_upgraded};
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateConfigArgs {
+  // the legacy layout has no room for them
+  pub curve_limits: AdaptiveCurveLimits,
+}
+
+// Upgrades the config created before versioning to the current layout,
+// markets can only be migrated once it is done
+#[derive(Accounts)]
+#[instruction(args: MigrateConfigArgs)]
+pub struct MigrateConfig<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  /// CHECK: legacy layout, decoded in handle
+  #[account(
+    mut,
+    owner = crate::ID,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: UncheckedAccount<'info>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+  pub fn validate(&self, args: &MigrateConfigArgs) -> Result<()> {
+    validate_curve_limits(&args.curve_limits)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: MigrateConfigArgs) -> Result<()> {
+    let MigrateConfig {
+      user,
+      config,
+      system_program,
+    } = ctx.accounts;
+
+    let space = 8 + Config::INIT_SPACE;
+    let legacy: LegacyConfig = load_legacy(config, &Config::DISCRIMINATOR, space)?;
+
+    // the stored authority is the only one that can upgrade
+    require!(
+      legacy.authority != Pubkey::default() && user.key() == legacy.authority,
+      MarketError::InvalidAuthority
+    );
+
+    let upgraded = legacy.upgrade(args.curve_limits);
+
+    write_upgraded(config, user, system_program, space, &upgraded)?;
+
+    emit!(events::MigrateConfig {
+      authority: upgraded.authority,
+      version: upgraded.version,
+      curve_limits: upgraded.curve_limits,
+    });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::create_market::validate_ltvs;
+use crate::error::MarketError;
+use crate::events;
+use crate::interest_rate::validate_irm;
+use crate::migration::{load_legacy, write_upgraded};
+use crate::oracle::oracle_init;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateMarketArgs {
+  // settings the legacy layout has no room for
+  pub oracle_config: OracleConfig,
+  pub irm: IrmKind,
+  pub liquidation_ltv: u64,
+}
+
+// Upgrades a market created before versioning to the current layout
+#[derive(Accounts)]
+#[instruction(args: MigrateMarketArgs)]
+pub struct MigrateMarket<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  /// CHECK: legacy layout, decoded and checked against its seeds in handle
+  #[account(mut, owner = crate::ID)]
+  pub market: UncheckedAccount<'info>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuthorityProtection<'info> for MigrateMarket<'info> {}
+
+impl<'info> MigrateMarket<'info> {
+  pub fn validate(&self, args: &MigrateMarketArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    validate_irm(&args.irm, &self.config.curve_limits)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: MigrateMarketArgs) -> Result<()> {
+    let MigrateMarket {
+      user,
+      market,
+      system_program,
+      ..
+    } = ctx.accounts;
+
+    let space = 8 + Market::INIT_SPACE;
+    let legacy: LegacyMarket = load_legacy(market, &Market::DISCRIMINATOR, space)?;
+
+    // seeds are unchanged across layouts
+    let address = Pubkey::create_program_address(
+      &[
+        MARKET_SEED_PREFIX,
+        legacy.quote_mint.as_ref(),
+        legacy.collateral_mint.as_ref(),
+        &legacy.ltv_factor.to_le_bytes(),
+        &legacy.oracle.id.to_bytes(),
+        &[legacy.bump],
+      ],
+      &crate::ID,
+    )
+    .map_err(|_| error!(MarketError::InvalidMigration))?;
+    require_keys_eq!(address, market.key(), MarketError::InvalidMigration);
+
+    let oracle = oracle_init(
+      &legacy.oracle.source.into(),
+      &legacy.oracle.id,
+      &Pubkey::default(),
+      &args.oracle_config,
+      None,
+    )?;
+    // the legacy ltv factor becomes the max borrow ltv, in LTV_PRECISION
+    let max_borrow_ltv = legacy.max_borrow_ltv()?;
+    validate_ltvs(max_borrow_ltv, args.liquidation_ltv)?;
+
+    let upgraded = legacy.upgrade(oracle, args.irm, max_borrow_ltv, args.liquidation_ltv);
+
+    write_upgraded(market, user, system_program, space, &upgraded)?;
+
+    emit!(events::MigrateMarket {
+      market: market.key(),
+      version: upgraded.version,
+      oracle_config: upgraded.oracle.config,
+      irm: upgraded.irm,
+      max_borrow_ltv: upgraded.max_borrow_ltv,
+      liquidation_ltv: upgraded.liquidation_ltv,
+    });
+
+    Ok(())
+  }
+}
@@ -1,31 +1,79 @@
+pub use accept_authority::*;
 pub use accrue_interest::*;
 pub use borrow::*;
+pub use cancel_authority::*;
+pub use close_borrower_position::*;
+pub use close_delegate::*;
+pub use close_lender_position::*;
 pub use create_market::*;
+pub use deleverage::*;
 pub use deposit::*;
 pub use deposit_collateral::*;
+pub use flash_loan::*;
+pub use flash_repay::*;
+pub use initialize_config::*;
 pub use interest_rate::*;
+pub use leverage::*;
 pub use liquidate::*;
+pub use migrate_config::*;
+pub use migrate_market::*;
+pub use pre_liquidate::*;
+pub use propose_authority::*;
 pub use repay::*;
-pub use update_authority::*;
+pub use set_fallback_oracle::*;
+pub use set_market_pause::*;
+pub use set_pre_liquidation_authorization::*;
+pub use update_curve_limits::*;
 pub use update_delegate::*;
 pub use update_fee::*;
+pub use update_guardian::*;
+pub use update_liquidation_auction::*;
+pub use update_liquidation_fee::*;
+pub use update_liquidation_limits::*;
+pub use update_liquidation_state::*;
+pub use update_market_caps::*;
+pub use update_pre_liquidation::*;
 pub use update_recipient::*;
 pub use views::*;
 pub use withdraw::*;
 pub use withdraw_collateral::*;
 pub use withdraw_fee::*;
 
+pub mod accept_authority;
 pub mod accrue_interest;
 pub mod borrow;
+pub mod cancel_authority;
+pub mod close_borrower_position;
+pub mod close_delegate;
+pub mod close_lender_position;
 pub mod create_market;
+pub mod deleverage;
 pub mod deposit;
 pub mod deposit_collateral;
+pub mod flash_loan;
+pub mod flash_repay;
+pub mod initialize_config;
 pub mod interest_rate;
+pub mod leverage;
 pub mod liquidate;
+pub mod migrate_config;
+pub mod migrate_market;
+pub mod pre_liquidate;
+pub mod propose_authority;
 pub mod repay;
-pub mod update_authority;
+pub mod set_fallback_oracle;
+pub mod set_market_pause;
+pub mod set_pre_liquidation_authorization;
+pub mod update_curve_limits;
 pub mod update_delegate;
 pub mod update_fee;
+pub mod update_guardian;
+pub mod update_liquidation_auction;
+pub mod update_liquidation_fee;
+pub mod update_liquidation_limits;
+pub mod update_liquidation_state;
+pub mod update_market_caps;
+pub mod update_pre_liquidation;
 pub mod update_recipient;
 pub mod views;
 pub mod withdraw;
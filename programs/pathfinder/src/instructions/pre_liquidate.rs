@@ -0,0 +1,392 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::MarketError;
+use crate::events;
+use crate::generate_market_seeds;
+use crate::math::*;
+use crate::oracle::{oracle_get_price, OracleAccounts};
+use crate::transfer::{amount_with_transfer_fee, transfer_from_vault, transfer_to_vault};
+use crate::{accrue_interest::accrue_interest, borrow::max_debt, state::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PreLiquidateArgs {
+  pub borrower: Pubkey,
+  pub collateral_amount: u64,
+  pub repay_shares: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: PreLiquidateArgs)]
+pub struct PreLiquidate<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      quote_mint.key().as_ref(),
+      collateral_mint.key().as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    mut,
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      args.borrower.as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Box<Account<'info, BorrowerShares>>,
+
+  #[account(
+    constraint = pre_liquidation_authorization.enabled @ MarketError::PreLiquidationNotAuthorized,
+    seeds = [
+      PRE_LIQUIDATION_SEED_PREFIX,
+      market.key().as_ref(),
+      args.borrower.as_ref()
+    ],
+    bump
+  )]
+  pub pre_liquidation_authorization: Box<Account<'info, PreLiquidationAuthorization>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = collateral_token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.collateral_mint,
+    associated_token::authority = market,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = collateral_mint,
+    associated_token::authority = user,
+    associated_token::token_program = collateral_token_program,
+  )]
+  pub user_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = quote_token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = market,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = market.quote_mint,
+    associated_token::authority = user,
+    associated_token::token_program = quote_token_program,
+  )]
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
+  /// CHECK: needed for dynamic oracle account
+  pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
+
+  pub quote_token_program: Interface<'info, TokenInterface>,
+  pub collateral_token_program: Interface<'info, TokenInterface>,
+  pub associated_token_program: Program<'info, AssociatedToken>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> PreLiquidate<'info> {
+  pub fn validate(&self) -> Result<()> {
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: PreLiquidateArgs) -> Result<()> {
+    let PreLiquidate {
+      user,
+      config,
+      market,
+      borrower_shares,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      quote_mint,
+      vault_ata_quote,
+      user_ata_quote,
+      oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+      quote_token_program,
+      collateral_token_program,
+      ..
+    } = ctx.accounts;
+
+    require!(!market.paused.liquidate, MarketError::MarketPaused);
+
+    let pre_liquidation = market.pre_liquidation;
+    require!(
+      pre_liquidation.pre_lltv != 0,
+      MarketError::PreLiquidationDisabled
+    );
+
+    let mut repay_shares = args.repay_shares;
+    let mut collateral_amount = args.collateral_amount;
+
+    // Validate that either shares or amount is zero, but not both
+    if (repay_shares == 0 && collateral_amount == 0)
+      || (repay_shares != 0 && collateral_amount != 0)
+    {
+      return err!(MarketError::AssetShareValueMismatch);
+    }
+
+    let old_borrower_borrow_shares = borrower_shares.borrow_shares;
+    let old_borrower_collateral = borrower_shares.collateral_amount;
+    let old_total_borrow_shares = market.total_borrow_shares;
+
+    accrue_interest(market, config)?;
+
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    // ltv is measured like in the solvency checks, low end of confidence interval
+    let price = oracle_get_price(&market.oracle, &oracle_accounts, false)?;
+
+    let total_borrows = market.total_borrows()?;
+    let borrowed = to_assets_up(
+      borrower_shares.borrow_shares,
+      total_borrows,
+      market.total_borrow_shares,
+    )?;
+    let collateral_value = collateral_to_quote_down(
+      borrower_shares.collateral_amount,
+      &price,
+      collateral_mint.decimals,
+      quote_mint.decimals,
+    )?;
+
+    // past the liquidation ltv the position goes through a full liquidation instead
+    require!(
+      (borrowed as u128) > max_debt(collateral_value, pre_liquidation.pre_lltv)?
+        && (borrowed as u128) <= max_debt(collateral_value, market.effective_liquidation_ltv())?,
+      MarketError::NotInPreLiquidationBand
+    );
+
+    let (close_factor, incentive_factor) = pre_liquidation_factors(
+      &pre_liquidation,
+      market.effective_liquidation_ltv(),
+      borrowed,
+      collateral_value,
+    )?;
+    let incentive_factor = Decimal::from_raw_u128(incentive_factor);
+
+    let collateral_price = oracle_get_price(&market.oracle, &oracle_accounts, true)?;
+
+    if collateral_amount > 0 {
+      let collateral_quoted = collateral_to_quote_up(
+        collateral_amount,
+        &collateral_price,
+        collateral_mint.decimals,
+        quote_mint.decimals,
+      )?;
+
+      repay_shares = to_shares_up(
+        Decimal::from_raw_u64(collateral_quoted)
+          .w_div_up(incentive_factor)?
+          .to_u64()?,
+        total_borrows,
+        market.total_borrow_shares,
+      )?;
+    } else {
+      let shares_to_collateral =
+        to_assets_down(repay_shares, total_borrows, market.total_borrow_shares)?;
+
+      let collateral_with_incentive = Decimal::from_raw_u64(shares_to_collateral)
+        .w_mul_down(incentive_factor)?
+        .to_u64()?;
+
+      collateral_amount = quote_to_collateral_down(
+        collateral_with_incentive,
+        &collateral_price,
+        collateral_mint.decimals,
+        quote_mint.decimals,
+      )?;
+    }
+
+    // only a bounded share of the position can be closed at once
+    let max_repay_shares = (borrower_shares.borrow_shares as u128)
+      .checked_mul(close_factor)
+      .ok_or(MarketError::MathOverflow)?
+      / WAD;
+    require!(
+      (repay_shares as u128) <= max_repay_shares,
+      MarketError::PreLiquidationCloseFactorExceeded
+    );
+
+    let repaid_quote = to_assets_up(repay_shares, total_borrows, market.total_borrow_shares)?;
+    // the liquidator covers any quote transfer fee
+    let repaid_quote_gross = amount_with_transfer_fee(quote_mint, repaid_quote)?;
+
+    require_gte!(
+      user_ata_quote.amount,
+      repaid_quote_gross,
+      MarketError::InsufficientBalance
+    );
+
+    borrower_shares.borrow_shares = borrower_shares
+      .borrow_shares
+      .checked_sub(repay_shares)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    market.total_borrow_shares = market
+      .total_borrow_shares
+      .checked_sub(repay_shares)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    borrower_shares.collateral_amount = borrower_shares
+      .collateral_amount
+      .checked_sub(collateral_amount)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    market.total_collateral = market
+      .total_collateral
+      .checked_sub(collateral_amount)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    // transfer tokens to liquidator
+    let seeds = generate_market_seeds!(market);
+    let signer = &[&seeds[..]];
+
+    transfer_from_vault(
+      collateral_token_program,
+      collateral_mint,
+      vault_ata_collateral,
+      user_ata_collateral,
+      &market.to_account_info(),
+      collateral_amount,
+      signer,
+    )?;
+
+    // transfer tokens to vault
+    let received = transfer_to_vault(
+      quote_token_program,
+      quote_mint,
+      user_ata_quote,
+      vault_ata_quote,
+      &user.to_account_info(),
+      repaid_quote_gross,
+    )?;
+    require_gte!(received, repaid_quote, MarketError::InsufficientBalance);
+
+    emit!(events::PreLiquidate {
+      market: market.key(),
+      liquidator: user.key(),
+      borrower: args.borrower,
+      repaid_quote,
+      repaid_shares: repay_shares,
+      seized_collateral: collateral_amount,
+      close_factor,
+      incentive_factor: incentive_factor.to_u128()?,
+      old_borrower_borrow_shares,
+      borrower_borrow_shares: borrower_shares.borrow_shares,
+      old_borrower_collateral,
+      borrower_collateral: borrower_shares.collateral_amount,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+    });
+
+    Ok(())
+  }
+}
+
+/// Checks a pre-liquidation config against the market liquidation ltv. The
+/// incentive may not exceed 1 / liquidation ltv, what the collateral is worth at
+/// the top of the band.
+pub fn validate_pre_liquidation(config: &PreLiquidationConfig, liquidation_ltv: u64) -> Result<()> {
+  // a zero pre_lltv disables pre-liquidations
+  if config.pre_lltv == 0 {
+    return Ok(());
+  }
+
+  require!(
+    config.pre_lltv < liquidation_ltv,
+    MarketError::InvalidPreLiquidationConfig
+  );
+  require!(
+    config.pre_lcf_1 > 0 && config.pre_lcf_1 <= config.pre_lcf_2 && config.pre_lcf_2 as u128 <= WAD,
+    MarketError::InvalidPreLiquidationConfig
+  );
+
+  let max_incentive_factor = WAD
+    .checked_mul(LTV_PRECISION)
+    .ok_or(MarketError::MathOverflow)?
+    / liquidation_ltv as u128;
+  require!(
+    config.pre_lif_1 as u128 >= WAD
+      && config.pre_lif_1 <= config.pre_lif_2
+      && config.pre_lif_2 as u128 <= max_incentive_factor,
+    MarketError::InvalidPreLiquidationConfig
+  );
+
+  Ok(())
+}
+
+/// Returns the close and incentive factors, scaled by WAD, interpolated on where
+/// the position ltv sits between pre_lltv and the liquidation ltv.
+fn pre_liquidation_factors(
+  config: &PreLiquidationConfig,
+  liquidation_ltv: u64,
+  borrowed: u64,
+  collateral_value: u64,
+) -> Result<(u128, u128)> {
+  // rounded up like the debt it is measured from
+  let ltv = mul_div_up(borrowed as u128, LTV_PRECISION, collateral_value as u128)?;
+
+  let quotient = ((ltv.saturating_sub(config.pre_lltv)) as u128)
+    .checked_mul(WAD)
+    .ok_or(MarketError::MathOverflow)?
+    / (liquidation_ltv - config.pre_lltv) as u128;
+  let quotient = quotient.min(WAD);
+
+  let interpolate = |start: u64, end: u64| -> Result<u128> {
+    Ok(
+      start as u128
+        + ((end - start) as u128)
+          .checked_mul(quotient)
+          .ok_or(MarketError::MathOverflow)?
+          / WAD,
+    )
+  };
+
+  Ok((
+    interpolate(config.pre_lcf_1, config.pre_lcf_2)?,
+    interpolate(config.pre_lif_1, config.pre_lif_2)?,
+  ))
+}
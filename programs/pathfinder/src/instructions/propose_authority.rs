@@ -1,24 +1,23 @@
 use anchor_lang::prelude::*;
 
 use crate::error::MarketError;
+use crate::events;
 use crate::state::*;
 use crate::traits::authority::AuthorityProtection;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct UpdateAuthorityArgs {
+pub struct ProposeAuthorityArgs {
   pub new_authority: Pubkey,
 }
 
 #[derive(Accounts)]
-#[instruction(args: UpdateAuthorityArgs)]
-pub struct UpdateAuthority<'info> {
+#[instruction(args: ProposeAuthorityArgs)]
+pub struct ProposeAuthority<'info> {
   #[account(mut)]
   pub user: Signer<'info>,
 
   #[account(
-    init_if_needed,
-    payer = user,
-    space = 8 + std::mem::size_of::<Config>(),
+    mut,
     seeds = [CONFIG_SEED_PREFIX],
     bump,
   )]
@@ -26,10 +25,10 @@ pub struct UpdateAuthority<'info> {
   pub system_program: Program<'info, System>,
 }
 
-impl<'info> AuthorityProtection<'info> for UpdateAuthority<'info> {}
+impl<'info> AuthorityProtection<'info> for ProposeAuthority<'info> {}
 
-impl<'info> UpdateAuthority<'info> {
-  pub fn validate(&self, args: &UpdateAuthorityArgs) -> Result<()> {
+impl<'info> ProposeAuthority<'info> {
+  pub fn validate(&self, args: &ProposeAuthorityArgs) -> Result<()> {
     self.is_authority(&self.user, &self.config)?;
     require!(
       args.new_authority != Pubkey::default(),
@@ -43,10 +42,16 @@ impl<'info> UpdateAuthority<'info> {
     Ok(())
   }
 
-  pub fn handle(ctx: Context<Self>, args: UpdateAuthorityArgs) -> Result<()> {
-    let UpdateAuthority { config, .. } = ctx.accounts;
+  pub fn handle(ctx: Context<Self>, args: ProposeAuthorityArgs) -> Result<()> {
+    let ProposeAuthority { config, .. } = ctx.accounts;
 
-    config.authority = args.new_authority;
+    // the current authority stays in charge until the new one accepts
+    config.pending_authority = args.new_authority;
+
+    emit!(events::ProposeAuthority {
+      authority: config.authority,
+      pending_authority: args.new_authority,
+    });
 
     Ok(())
   }
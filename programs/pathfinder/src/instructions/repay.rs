@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
 use crate::math::*;
+use crate::transfer::{amount_with_transfer_fee, transfer_to_vault};
 use crate::{accrue_interest::accrue_interest, state::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -32,7 +34,7 @@ pub struct Repay<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -50,27 +52,32 @@ pub struct Repay<'info> {
   )]
   pub borrower_shares: Box<Account<'info, BorrowerShares>>,
 
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = user,
+    associated_token::token_program = token_program,
   )]
-  pub user_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub user_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -86,6 +93,7 @@ impl<'info> Repay<'info> {
       config,
       market,
       borrower_shares,
+      quote_mint,
       user_ata_quote,
       vault_ata_quote,
       token_program,
@@ -102,14 +110,37 @@ impl<'info> Repay<'info> {
 
     msg!("repaying {}", assets);
 
+    let old_owner_borrow_shares = borrower_shares.borrow_shares;
+    let old_total_borrow_shares = market.total_borrow_shares;
+    let old_borrow_index = market.borrow_index;
+
     accrue_interest(market, config)?;
 
     let total_borrows = market.total_borrows()?;
 
     if assets > 0 {
+      // credit what the vault actually received
+      assets = transfer_to_vault(
+        token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        assets,
+      )?;
       shares = to_shares_down(assets, total_borrows, market.total_borrow_shares)?;
     } else {
       assets = to_assets_up(shares, total_borrows, market.total_borrow_shares)?;
+
+      let received = transfer_to_vault(
+        token_program,
+        quote_mint,
+        user_ata_quote,
+        vault_ata_quote,
+        &user.to_account_info(),
+        amount_with_transfer_fee(quote_mint, assets)?,
+      )?;
+      require_gte!(received, assets, MarketError::InsufficientBalance);
     }
 
     // Update market shares
@@ -124,18 +155,19 @@ impl<'info> Repay<'info> {
       .checked_sub(shares)
       .ok_or(MarketError::MathUnderflow)?;
 
-    // Create CpiContext for the transfer
-    let cpi_context = CpiContext::new(
-      token_program.to_account_info(),
-      Transfer {
-        from: user_ata_quote.to_account_info(),
-        to: vault_ata_quote.to_account_info(),
-        authority: user.to_account_info(),
-      },
-    );
-
-    // transfer tokens to vault
-    transfer(cpi_context, assets)?;
+    emit!(events::Repay {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      assets,
+      shares,
+      old_owner_borrow_shares,
+      owner_borrow_shares: borrower_shares.borrow_shares,
+      old_total_borrow_shares,
+      total_borrow_shares: market.total_borrow_shares,
+      old_borrow_index,
+      borrow_index: market.borrow_index,
+    });
 
     Ok(())
   }
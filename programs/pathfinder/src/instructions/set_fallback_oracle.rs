@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::events;
+use crate::oracle::validate_fallback;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetFallbackOracleArgs {
+  pub fallback_oracle: Option<FallbackOracle>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetFallbackOracleArgs)]
+pub struct SetFallbackOracle<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for SetFallbackOracle<'info> {}
+
+impl<'info> SetFallbackOracle<'info> {
+  pub fn validate(&self, args: &SetFallbackOracleArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+
+    // None removes the fallback
+    if let Some(fallback) = &args.fallback_oracle {
+      validate_fallback(&self.market.oracle, fallback)?;
+    }
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: SetFallbackOracleArgs) -> Result<()> {
+    let SetFallbackOracle { market, .. } = ctx.accounts;
+
+    market.oracle.fallback = args.fallback_oracle;
+
+    emit!(events::SetFallbackOracle {
+      market: market.key(),
+      fallback_oracle: market.oracle.fallback,
+    });
+
+    Ok(())
+  }
+}
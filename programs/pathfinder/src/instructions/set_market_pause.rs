@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMarketPauseArgs {
+  pub paused: MarketPause,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetMarketPauseArgs)]
+pub struct SetMarketPause<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for SetMarketPause<'info> {}
+
+impl<'info> SetMarketPause<'info> {
+  pub fn validate(&self) -> Result<()> {
+    self.is_guardian(&self.user, &self.config)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: SetMarketPauseArgs) -> Result<()> {
+    let SetMarketPause { user, market, .. } = ctx.accounts;
+
+    if market.is_wiped_out() {
+      require!(
+        args.paused.deposit && args.paused.borrow,
+        MarketError::MarketWipedOut
+      );
+    }
+
+    // repay and deposit_collateral are never paused so borrowers can always
+    // improve their position
+    market.paused = args.paused;
+
+    emit!(events::SetMarketPause {
+      market: market.key(),
+      user: user.key(),
+      paused: args.paused,
+    });
+
+    Ok(())
+  }
+}
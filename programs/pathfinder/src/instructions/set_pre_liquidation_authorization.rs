@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::events;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetPreLiquidationAuthorizationArgs {
+  pub enabled: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SetPreLiquidationAuthorizationArgs)]
+pub struct SetPreLiquidationAuthorization<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub market: Box<Account<'info, Market>>,
+
+  // borrower opt-in, per market
+  #[account(
+    init_if_needed,
+    payer = user,
+    space = 8 + std::mem::size_of::<PreLiquidationAuthorization>(),
+    seeds = [
+      PRE_LIQUIDATION_SEED_PREFIX,
+      market.key().as_ref(),
+      user.key().as_ref(),
+    ],
+    bump
+  )]
+  pub pre_liquidation_authorization: Box<Account<'info, PreLiquidationAuthorization>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetPreLiquidationAuthorization<'info> {
+  pub fn validate(&self) -> Result<()> {
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: SetPreLiquidationAuthorizationArgs) -> Result<()> {
+    let SetPreLiquidationAuthorization {
+      user,
+      market,
+      pre_liquidation_authorization,
+      ..
+    } = ctx.accounts;
+
+    pre_liquidation_authorization.bump = ctx.bumps.pre_liquidation_authorization;
+    pre_liquidation_authorization.enabled = args.enabled;
+
+    emit!(events::SetPreLiquidationAuthorization {
+      market: market.key(),
+      owner: user.key(),
+      enabled: args.enabled,
+    });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::events;
+use crate::interest_rate::validate_curve_limits;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateCurveLimitsArgs {
+  pub new_curve_limits: AdaptiveCurveLimits,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateCurveLimitsArgs)]
+pub struct UpdateCurveLimits<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateCurveLimits<'info> {}
+
+impl<'info> UpdateCurveLimits<'info> {
+  pub fn validate(&self, args: &UpdateCurveLimitsArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    validate_curve_limits(&args.new_curve_limits)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateCurveLimitsArgs) -> Result<()> {
+    let UpdateCurveLimits { config, .. } = ctx.accounts;
+
+    emit!(events::UpdateCurveLimits {
+      old_curve_limits: config.curve_limits,
+      new_curve_limits: args.new_curve_limits,
+    });
+
+    // only bounds markets created from now on
+    config.curve_limits = args.new_curve_limits;
+
+    Ok(())
+  }
+}
@@ -1,3 +1,4 @@
+use crate::events;
 use crate::state::{PositionDelegate, DELEGATE_SEED_PREFIX};
 use anchor_lang::prelude::*;
 
@@ -34,9 +35,17 @@ impl<'info> UpdateDelegate<'info> {
 
   pub fn handle(ctx: Context<Self>, args: UpdateDelegateArgs) -> Result<()> {
     let UpdateDelegate {
-      position_delegate, ..
+      user,
+      position_delegate,
+      ..
     } = ctx.accounts;
 
+    emit!(events::UpdateDelegate {
+      owner: user.key(),
+      old_delegate: position_delegate.delegate,
+      new_delegate: args.new_delegate,
+    });
+
     position_delegate.delegate = args.new_delegate;
 
     Ok(())
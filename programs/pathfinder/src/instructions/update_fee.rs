@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::error::MarketError;
+use crate::events;
 use crate::state::*;
 use crate::traits::authority::AuthorityProtection;
 
@@ -16,9 +17,7 @@ pub struct UpdateFee<'info> {
   pub user: Signer<'info>,
 
   #[account(
-    init_if_needed,
-    payer = user,
-    space = 8 + std::mem::size_of::<Config>(),
+    mut,
     seeds = [CONFIG_SEED_PREFIX],
     bump,
   )]
@@ -48,6 +47,11 @@ impl<'info> UpdateFee<'info> {
   pub fn handle(ctx: Context<Self>, args: UpdateFeeArgs) -> Result<()> {
     let UpdateFee { config, .. } = ctx.accounts;
 
+    emit!(events::UpdateFee {
+      old_fee_factor: config.fee_factor,
+      new_fee_factor: args.new_fee_factor,
+    });
+
     config.fee_factor = args.new_fee_factor;
 
     Ok(())
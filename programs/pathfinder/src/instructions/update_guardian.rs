@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateGuardianArgs {
+  pub new_guardian: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateGuardianArgs)]
+pub struct UpdateGuardian<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateGuardian<'info> {}
+
+impl<'info> UpdateGuardian<'info> {
+  pub fn validate(&self, args: &UpdateGuardianArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    // the default key removes the guardian
+    require!(
+      args.new_guardian != self.config.guardian,
+      MarketError::InvalidGuardian
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateGuardianArgs) -> Result<()> {
+    let UpdateGuardian { config, .. } = ctx.accounts;
+
+    emit!(events::UpdateGuardian {
+      old_guardian: config.guardian,
+      new_guardian: args.new_guardian,
+    });
+
+    config.guardian = args.new_guardian;
+
+    Ok(())
+  }
+}
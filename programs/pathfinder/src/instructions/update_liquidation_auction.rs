@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateLiquidationAuctionArgs {
+  // seconds, zero turns the auction off
+  pub duration: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateLiquidationAuctionArgs)]
+pub struct UpdateLiquidationAuction<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateLiquidationAuction<'info> {}
+
+impl<'info> UpdateLiquidationAuction<'info> {
+  pub fn validate(&self, args: &UpdateLiquidationAuctionArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    require_gte!(
+      MAX_LIQUIDATION_AUCTION_DURATION,
+      args.duration,
+      MarketError::InvalidLiquidationAuctionDuration
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateLiquidationAuctionArgs) -> Result<()> {
+    let UpdateLiquidationAuction { market, .. } = ctx.accounts;
+
+    emit!(events::UpdateLiquidationAuction {
+      market: market.key(),
+      old_duration: market.liquidation_auction_duration,
+      new_duration: args.duration,
+    });
+
+    market.liquidation_auction_duration = args.duration;
+
+    Ok(())
+  }
+}
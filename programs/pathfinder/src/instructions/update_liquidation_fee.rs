@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateLiquidationFeeArgs {
+  pub new_liquidation_fee_factor: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateLiquidationFeeArgs)]
+pub struct UpdateLiquidationFee<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateLiquidationFee<'info> {}
+
+impl<'info> UpdateLiquidationFee<'info> {
+  pub fn validate(&self, args: &UpdateLiquidationFeeArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+
+    require!(
+      args.new_liquidation_fee_factor <= MAX_LIQUIDATION_FEE_FACTOR,
+      MarketError::FeeExceedsMax
+    );
+
+    require!(
+      args.new_liquidation_fee_factor != self.config.liquidation_fee_factor,
+      MarketError::FeeAlreadySet
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateLiquidationFeeArgs) -> Result<()> {
+    let UpdateLiquidationFee { config, .. } = ctx.accounts;
+
+    emit!(events::UpdateLiquidationFee {
+      old_liquidation_fee_factor: config.liquidation_fee_factor,
+      new_liquidation_fee_factor: args.new_liquidation_fee_factor,
+    });
+
+    config.liquidation_fee_factor = args.new_liquidation_fee_factor;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::events;
+use crate::math::WAD;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateLiquidationLimitsArgs {
+  pub limits: LiquidationLimits,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateLiquidationLimitsArgs)]
+pub struct UpdateLiquidationLimits<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateLiquidationLimits<'info> {}
+
+impl<'info> UpdateLiquidationLimits<'info> {
+  pub fn validate(&self, args: &UpdateLiquidationLimitsArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+
+    // a position is liquidatable below a health of one, higher thresholds lift the cap always
+    let limits = &args.limits;
+    require!(
+      limits.close_factor as u128 <= WAD && limits.full_close_health as u128 <= WAD,
+      MarketError::InvalidLiquidationLimits
+    );
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateLiquidationLimitsArgs) -> Result<()> {
+    let UpdateLiquidationLimits { market, .. } = ctx.accounts;
+
+    emit!(events::UpdateLiquidationLimits {
+      market: market.key(),
+      old_limits: market.liquidation_limits,
+      new_limits: args.limits,
+    });
+
+    market.liquidation_limits = args.limits;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::events;
+use crate::oracle::OracleAccounts;
+use crate::{accrue_interest::accrue_interest, borrow::is_liquidatable, state::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateLiquidationStateArgs {
+  pub borrower: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateLiquidationStateArgs)]
+pub struct UpdateLiquidationState<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+
+  #[account(
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      args.borrower.as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Box<Account<'info, BorrowerShares>>,
+
+  // anyone may create it, the position alone decides what is recorded
+  #[account(
+    init_if_needed,
+    payer = user,
+    space = 8 + std::mem::size_of::<LiquidationState>(),
+    seeds = [
+      LIQUIDATION_STATE_SEED_PREFIX,
+      market.key().as_ref(),
+      args.borrower.as_ref()
+    ],
+    bump
+  )]
+  pub liquidation_state: Box<Account<'info, LiquidationState>>,
+
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+  /// CHECK: needed for dynamic oracle account
+  pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
+
+  pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateLiquidationState<'info> {
+  pub fn validate(&self) -> Result<()> {
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateLiquidationStateArgs) -> Result<()> {
+    let UpdateLiquidationState {
+      config,
+      market,
+      borrower_shares,
+      liquidation_state,
+      collateral_mint,
+      oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+      ..
+    } = ctx.accounts;
+
+    accrue_interest(market, config)?;
+
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    liquidation_state.bump = ctx.bumps.liquidation_state;
+
+    // the first record stands until the position recovers or changes, so the auction cannot be restarted
+    if is_liquidatable(
+      market,
+      &oracle_accounts,
+      borrower_shares.borrow_shares,
+      borrower_shares.collateral_amount,
+      collateral_mint.decimals,
+    )? {
+      if !liquidation_state.is_current(borrower_shares) {
+        liquidation_state.record(Clock::get()?.unix_timestamp as u64, borrower_shares);
+      }
+    } else {
+      liquidation_state.clear();
+    }
+
+    emit!(events::UpdateLiquidationState {
+      market: market.key(),
+      borrower: args.borrower,
+      insolvent_since: liquidation_state.insolvent_since,
+    });
+
+    Ok(())
+  }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::events;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateMarketCapsArgs {
+  pub supply_cap: u64,
+  pub borrow_cap: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdateMarketCapsArgs)]
+pub struct UpdateMarketCaps<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdateMarketCaps<'info> {}
+
+impl<'info> UpdateMarketCaps<'info> {
+  pub fn validate(&self) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdateMarketCapsArgs) -> Result<()> {
+    let UpdateMarketCaps { market, .. } = ctx.accounts;
+
+    // lowering a cap below the current totals only blocks new deposits or borrows
+    emit!(events::UpdateMarketCaps {
+      market: market.key(),
+      old_supply_cap: market.supply_cap,
+      new_supply_cap: args.supply_cap,
+      old_borrow_cap: market.borrow_cap,
+      new_borrow_cap: args.borrow_cap,
+    });
+
+    market.supply_cap = args.supply_cap;
+    market.borrow_cap = args.borrow_cap;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::events;
+use crate::pre_liquidate::validate_pre_liquidation;
+use crate::state::*;
+use crate::traits::authority::AuthorityProtection;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdatePreLiquidationArgs {
+  pub pre_liquidation: PreLiquidationConfig,
+}
+
+#[derive(Accounts)]
+#[instruction(args: UpdatePreLiquidationArgs)]
+pub struct UpdatePreLiquidation<'info> {
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Box<Account<'info, Config>>,
+
+  #[account(
+    mut,
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> AuthorityProtection<'info> for UpdatePreLiquidation<'info> {}
+
+impl<'info> UpdatePreLiquidation<'info> {
+  pub fn validate(&self, args: &UpdatePreLiquidationArgs) -> Result<()> {
+    self.is_authority(&self.user, &self.config)?;
+    validate_pre_liquidation(&args.pre_liquidation, self.market.effective_liquidation_ltv())?;
+
+    Ok(())
+  }
+
+  pub fn handle(ctx: Context<Self>, args: UpdatePreLiquidationArgs) -> Result<()> {
+    let UpdatePreLiquidation { market, .. } = ctx.accounts;
+
+    emit!(events::UpdatePreLiquidation {
+      market: market.key(),
+      old_pre_liquidation: market.pre_liquidation,
+      new_pre_liquidation: args.pre_liquidation,
+    });
+
+    market.pre_liquidation = args.pre_liquidation;
+
+    Ok(())
+  }
+}
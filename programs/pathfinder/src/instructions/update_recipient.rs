@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::error::MarketError;
+use crate::events;
 use crate::state::*;
 use crate::traits::authority::AuthorityProtection;
 
@@ -16,9 +17,7 @@ pub struct UpdateRecipient<'info> {
   pub user: Signer<'info>,
 
   #[account(
-    init_if_needed,
-    payer = user,
-    space = 8 + std::mem::size_of::<Config>(),
+    mut,
     seeds = [CONFIG_SEED_PREFIX],
     bump,
   )]
@@ -42,6 +41,11 @@ impl<'info> UpdateRecipient<'info> {
   pub fn handle(ctx: Context<Self>, args: UpdateRecipientArgs) -> Result<()> {
     let UpdateRecipient { config, .. } = ctx.accounts;
 
+    emit!(events::UpdateRecipient {
+      old_recipient: config.fee_recipient,
+      new_recipient: args.new_recipient,
+    });
+
     config.fee_recipient = args.new_recipient;
 
     Ok(())
@@ -3,7 +3,6 @@ use crate::interest_rate::get_rate;
 use crate::math::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
 
 #[derive(Accounts)]
 pub struct ViewMarket<'info> {
@@ -20,7 +19,7 @@ pub struct ViewMarket<'info> {
         MARKET_SEED_PREFIX,
         &market.quote_mint.key().as_ref(),
         &market.collateral_mint.key().as_ref(),
-        &market.ltv_factor.to_le_bytes(),
+        &market.seed_ltv_bytes(),
         &market.oracle.id.to_bytes(),
       ],
       bump = market.bump,
@@ -0,0 +1,108 @@
+use crate::borrow::max_debt;
+use crate::math::*;
+use crate::oracle::{oracle_get_price, OracleAccounts};
+use crate::state::*;
+use crate::views::expected_market_balances;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct ViewPosition<'info> {
+  // config
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Account<'info, Config>,
+
+  // market
+  #[account(
+    seeds = [
+      MARKET_SEED_PREFIX,
+      market.quote_mint.as_ref(),
+      market.collateral_mint.as_ref(),
+      &market.seed_ltv_bytes(),
+      &market.oracle.id.to_bytes(),
+    ],
+    bump = market.bump,
+  )]
+  pub market: Account<'info, Market>,
+
+  #[account(
+    seeds = [
+      BORROWER_SHARES_SEED_PREFIX,
+      market.key().as_ref(),
+      owner.as_ref()
+    ],
+    bump
+  )]
+  pub borrower_shares: Account<'info, BorrowerShares>,
+
+  /// CHECK: needed for dynamic oracle account
+  pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
+}
+
+impl<'info> ViewPosition<'info> {
+  /// Returns the health of a position against the max borrow ltv and against the
+  /// liquidation ltv, after having accrued interest.
+  /// Health is the debt the collateral supports over the debt, scaled by WAD: below
+  /// 1 the position can no longer borrow, respectively can be liquidated.
+  /// Returns u64::MAX for a position without debt.
+  pub fn expected_health(ctx: Context<ViewPosition<'info>>) -> Result<(u64, u64)> {
+    let ViewPosition {
+      config,
+      market,
+      borrower_shares,
+      oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
+    } = ctx.accounts;
+
+    let (_, _, total_borrows, total_borrow_shares) = expected_market_balances(market, config)?;
+    let borrowed = to_assets_up(
+      borrower_shares.borrow_shares,
+      total_borrows,
+      total_borrow_shares,
+    )?;
+
+    if borrowed == 0 {
+      return Ok((u64::MAX, u64::MAX));
+    }
+
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
+    // same price as the solvency checks, low end of confidence interval
+    let price = oracle_get_price(&market.oracle, &oracle_accounts, false)?;
+    let collateral_value = collateral_to_quote_down(
+      borrower_shares.collateral_amount,
+      &price,
+      market.collateral_mint_decimals,
+      market.quote_mint_decimals,
+    )?;
+
+    Ok((
+      position_health(collateral_value, borrowed, market.max_borrow_ltv)?,
+      position_health(collateral_value, borrowed, market.effective_liquidation_ltv())?,
+    ))
+  }
+}
+
+/// Debt allowed at `ltv` over the actual debt, scaled by WAD and capped at u64::MAX.
+pub fn position_health(collateral_value: u64, borrowed: u64, ltv: u64) -> Result<u64> {
+  let health =
+    U256::from(max_debt(collateral_value, ltv)?) * U256::from(WAD) / U256::from(borrowed);
+
+  Ok(if health > U256::from(u64::MAX) {
+    u64::MAX
+  } else {
+    health.as_u64()
+  })
+}
@@ -1,2 +1,4 @@
 pub mod balances;
+pub mod health;
 pub use balances::*;
+pub use health::*;
@@ -1,9 +1,11 @@
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
+use crate::transfer::transfer_from_vault;
 use crate::{accrue_interest::accrue_interest, generate_market_seeds, math::*};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -49,7 +51,7 @@ pub struct Withdraw<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -67,28 +69,33 @@ pub struct Withdraw<'info> {
   )]
   pub lender_shares: Box<Account<'info, LenderShares>>,
 
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = recipient,
     associated_token::mint = quote_mint,
+    associated_token::token_program = token_program,
   )]
-  pub recipient_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub recipient_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -100,18 +107,27 @@ impl<'info> Withdraw<'info> {
 
   pub fn handle(ctx: Context<Self>, args: WithdrawArgs) -> Result<()> {
     let Withdraw {
+      user,
+      recipient,
       config,
       market,
       lender_shares,
+      quote_mint,
       recipient_ata_quote,
       vault_ata_quote,
       token_program,
       ..
     } = ctx.accounts;
 
+    require!(!market.paused.withdraw, MarketError::MarketPaused);
+
     let mut shares = args.shares;
     let mut assets = args.amount;
 
+    let old_owner_shares = lender_shares.shares;
+    let old_total_shares = market.total_shares;
+    let old_deposit_index = market.deposit_index;
+
     process_withdrawal_and_transfer(
       market,
       config,
@@ -119,11 +135,27 @@ impl<'info> Withdraw<'info> {
       &mut assets,
       false,
       Some(lender_shares),
+      quote_mint,
       vault_ata_quote,
       recipient_ata_quote,
       token_program,
     )?;
 
+    emit!(events::Withdraw {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      recipient: recipient.key(),
+      assets,
+      shares,
+      old_owner_shares,
+      owner_shares: lender_shares.shares,
+      old_total_shares,
+      total_shares: market.total_shares,
+      old_deposit_index,
+      deposit_index: market.deposit_index,
+    });
+
     Ok(())
   }
 }
@@ -135,9 +167,10 @@ pub fn process_withdrawal_and_transfer<'info>(
   assets: &mut u64,
   is_fee_recipient: bool,
   lender_shares: Option<&mut Account<'info, LenderShares>>,
-  vault_ata_quote: &Account<'info, TokenAccount>,
-  recipient_ata_quote: &Account<'info, TokenAccount>,
-  token_program: &Program<'info, Token>,
+  quote_mint: &InterfaceAccount<'info, Mint>,
+  vault_ata_quote: &InterfaceAccount<'info, TokenAccount>,
+  recipient_ata_quote: &InterfaceAccount<'info, TokenAccount>,
+  token_program: &Interface<'info, TokenInterface>,
 ) -> Result<()> {
   // Process withdrawal amounts
   if (*shares == 0 && *assets == 0) || (*shares != 0 && *assets != 0) {
@@ -177,17 +210,14 @@ pub fn process_withdrawal_and_transfer<'info>(
   let seeds = generate_market_seeds!(market);
   let signer = &[&seeds[..]];
 
-  transfer(
-    CpiContext::new_with_signer(
-      token_program.to_account_info(),
-      Transfer {
-        from: vault_ata_quote.to_account_info(),
-        to: recipient_ata_quote.to_account_info(),
-        authority: market.to_account_info(),
-      },
-      signer,
-    ),
+  transfer_from_vault(
+    token_program,
+    quote_mint,
+    vault_ata_quote,
+    recipient_ata_quote,
+    &market.to_account_info(),
     *assets,
+    signer,
   )?;
 
   Ok(())
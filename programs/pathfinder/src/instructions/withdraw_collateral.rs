@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::error::MarketError;
+use crate::events;
+use crate::oracle::OracleAccounts;
+use crate::transfer::transfer_from_vault;
 use crate::{
   accrue_interest::accrue_interest, borrow::is_solvent, generate_market_seeds, state::*,
 };
@@ -49,7 +52,7 @@ pub struct WithdrawCollateral<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
@@ -68,30 +71,39 @@ pub struct WithdrawCollateral<'info> {
   pub borrower_shares: Box<Account<'info, BorrowerShares>>,
 
   #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = collateral_mint.key() == market.collateral_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = collateral_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = recipient,
     associated_token::mint = collateral_mint,
+    associated_token::token_program = token_program,
   )]
-  pub recipient_ata_collateral: Box<Account<'info, TokenAccount>>,
+  pub recipient_ata_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   /// CHECK: needed for dynamic oracle account
   pub oracle_ai: AccountInfo<'info>,
+  /// CHECK: quote feed, only read by composite oracles
+  pub quote_oracle_ai: Option<AccountInfo<'info>>,
+  /// CHECK: secondary feed, only read when the market has a fallback
+  pub fallback_oracle_ai: Option<AccountInfo<'info>>,
   pub system_program: Program<'info, System>,
 }
 
@@ -106,6 +118,8 @@ impl<'info> WithdrawCollateral<'info> {
 
   pub fn handle(ctx: Context<Self>, args: WithdrawCollateralArgs) -> Result<()> {
     let WithdrawCollateral {
+      user,
+      recipient,
       config,
       market,
       borrower_shares,
@@ -114,11 +128,21 @@ impl<'info> WithdrawCollateral<'info> {
       vault_ata_collateral,
       token_program,
       oracle_ai,
+      quote_oracle_ai,
+      fallback_oracle_ai,
       ..
     } = ctx.accounts;
 
+    require!(
+      !market.paused.withdraw_collateral,
+      MarketError::MarketPaused
+    );
+
     let assets = args.amount;
 
+    let old_owner_collateral = borrower_shares.collateral_amount;
+    let old_total_collateral = market.total_collateral;
+
     accrue_interest(market, &config)?;
 
     // check if user is solvent after withdrawing collateral
@@ -127,9 +151,15 @@ impl<'info> WithdrawCollateral<'info> {
       .checked_sub(assets)
       .unwrap();
 
+    let oracle_accounts = OracleAccounts {
+      oracle_ai,
+      quote_oracle_ai: quote_oracle_ai.as_ref(),
+      fallback_oracle_ai: fallback_oracle_ai.as_ref(),
+    };
+
     if !is_solvent(
       market,
-      &oracle_ai,
+      &oracle_accounts,
       borrower_shares.borrow_shares,
       updated_collateral_amount,
       collateral_mint.decimals,
@@ -155,20 +185,29 @@ impl<'info> WithdrawCollateral<'info> {
     let seeds = generate_market_seeds!(market);
     let signer = &[&seeds[..]];
 
-    // Transfer collateral tokens from user to vault
-    transfer(
-      CpiContext::new_with_signer(
-        token_program.to_account_info(),
-        Transfer {
-          from: vault_ata_collateral.to_account_info(),
-          to: recipient_ata_collateral.to_account_info(),
-          authority: market.to_account_info(),
-        },
-        signer,
-      ),
+    // Transfer collateral tokens from vault to recipient
+    transfer_from_vault(
+      token_program,
+      collateral_mint,
+      vault_ata_collateral,
+      recipient_ata_collateral,
+      &market.to_account_info(),
       assets,
+      signer,
     )?;
 
+    emit!(events::WithdrawCollateral {
+      market: market.key(),
+      user: user.key(),
+      owner: args.owner,
+      recipient: recipient.key(),
+      amount: assets,
+      old_owner_collateral,
+      owner_collateral: borrower_shares.collateral_amount,
+      old_total_collateral,
+      total_collateral: market.total_collateral,
+    });
+
     Ok(())
   }
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
 use crate::error::MarketError;
+use crate::events;
 use crate::instructions::withdraw::process_withdrawal_and_transfer;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct WithdrawFeeArgs {
@@ -34,35 +35,40 @@ pub struct WithdrawFee<'info> {
       MARKET_SEED_PREFIX,
       quote_mint.key().as_ref(),
       collateral_mint.key().as_ref(),
-      &market.ltv_factor.to_le_bytes(),
+      &market.seed_ltv_bytes(),
       &market.oracle.id.to_bytes(),
     ],
     bump = market.bump,
   )]
   pub market: Box<Account<'info, Market>>,
 
-  #[account(constraint = quote_mint.key() == market.quote_mint.key())]
-  pub quote_mint: Box<Account<'info, Mint>>,
+  #[account(
+    constraint = quote_mint.key() == market.quote_mint.key(),
+    mint::token_program = token_program,
+  )]
+  pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
   #[account(
     mut,
     associated_token::mint = market.quote_mint,
     associated_token::authority = market,
+    associated_token::token_program = token_program,
   )]
-  pub vault_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub vault_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::authority = recipient,
     associated_token::mint = quote_mint,
+    associated_token::token_program = token_program,
   )]
-  pub recipient_ata_quote: Box<Account<'info, TokenAccount>>,
+  pub recipient_ata_quote: Box<InterfaceAccount<'info, TokenAccount>>,
 
   #[account(constraint = collateral_mint.key() == market.collateral_mint.key())]
-  pub collateral_mint: Box<Account<'info, Mint>>,
+  pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
-  pub token_program: Program<'info, Token>,
+  pub token_program: Interface<'info, TokenInterface>,
   pub associated_token_program: Program<'info, AssociatedToken>,
   pub system_program: Program<'info, System>,
 }
@@ -83,17 +89,23 @@ impl<'info> WithdrawFee<'info> {
 
   pub fn handle(ctx: Context<Self>, args: WithdrawFeeArgs) -> Result<()> {
     let WithdrawFee {
+      recipient,
       config,
       market,
+      quote_mint,
       recipient_ata_quote,
       vault_ata_quote,
       token_program,
       ..
     } = ctx.accounts;
 
+    require!(!market.paused.withdraw, MarketError::MarketPaused);
+
     let mut shares = args.shares;
     let mut assets = args.amount;
 
+    let old_fee_shares = market.fee_shares;
+
     process_withdrawal_and_transfer(
       market,
       config,
@@ -101,11 +113,21 @@ impl<'info> WithdrawFee<'info> {
       &mut assets,
       true,
       None,
+      quote_mint,
       vault_ata_quote,
       recipient_ata_quote,
       token_program,
     )?;
 
+    emit!(events::WithdrawFee {
+      market: market.key(),
+      recipient: recipient.key(),
+      assets,
+      shares,
+      old_fee_shares,
+      fee_shares: market.fee_shares,
+    });
+
     Ok(())
   }
 }
\ No newline at end of file
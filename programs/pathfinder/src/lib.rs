@@ -2,12 +2,16 @@ use anchor_lang::prelude::*;
 
 declare_id!("7ALFC87zvuPvpp9h5Stq9SSP3kTCUJfhtirEZVJmZYy4");
 
+pub mod callback;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod math;
+pub mod migration;
 pub mod oracle;
 pub mod state;
 pub mod traits;
+pub mod transfer;
 
 use crate::instructions::*;
 
@@ -21,6 +25,12 @@ pub mod pathfinder {
     UpdateDelegate::handle(ctx, args)
   }
 
+  #[access_control(ctx.accounts.validate())]
+  pub fn close_delegate(ctx: Context<CloseDelegate>) -> Result<()> {
+    CloseDelegate::handle(ctx)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
   pub fn create_market(ctx: Context<CreateMarket>, args: CreateMarketArgs) -> Result<()> {
     CreateMarket::handle(ctx, args)
   }
@@ -45,6 +55,16 @@ pub mod pathfinder {
     WithdrawCollateral::handle(ctx, args)
   }
 
+  #[access_control(ctx.accounts.validate())]
+  pub fn close_lender_position(ctx: Context<CloseLenderPosition>) -> Result<()> {
+    CloseLenderPosition::handle(ctx)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn close_borrower_position(ctx: Context<CloseBorrowerPosition>) -> Result<()> {
+    CloseBorrowerPosition::handle(ctx)
+  }
+
   #[access_control(ctx.accounts.validate(&args))]
   pub fn deposit_collateral(
     ctx: Context<DepositCollateral>,
@@ -57,22 +77,171 @@ pub mod pathfinder {
     AccrueInterest::handle(ctx)
   }
 
-  pub fn liquidate(ctx: Context<Liquidate>, args: LiquidateArgs) -> Result<()> {
+  pub fn liquidate<'info>(
+    ctx: Context<'_, '_, '_, 'info, Liquidate<'info>>,
+    args: LiquidateArgs,
+  ) -> Result<()> {
     Liquidate::handle(ctx, args)
   }
 
+  pub fn pre_liquidate(ctx: Context<PreLiquidate>, args: PreLiquidateArgs) -> Result<()> {
+    PreLiquidate::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn update_liquidation_state(
+    ctx: Context<UpdateLiquidationState>,
+    args: UpdateLiquidationStateArgs,
+  ) -> Result<()> {
+    UpdateLiquidationState::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn set_pre_liquidation_authorization(
+    ctx: Context<SetPreLiquidationAuthorization>,
+    args: SetPreLiquidationAuthorizationArgs,
+  ) -> Result<()> {
+    SetPreLiquidationAuthorization::handle(ctx, args)
+  }
+
   pub fn repay(ctx: Context<Repay>, args: RepayArgs) -> Result<()> {
     Repay::handle(ctx, args)
   }
 
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn deleverage<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deleverage<'info>>,
+    args: DeleverageArgs,
+  ) -> Result<()> {
+    Deleverage::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn leverage<'info>(
+    ctx: Context<'_, '_, '_, 'info, Leverage<'info>>,
+    args: LeverageArgs,
+  ) -> Result<()> {
+    Leverage::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn flash_loan<'info>(
+    ctx: Context<'_, '_, '_, 'info, FlashLoan<'info>>,
+    args: FlashLoanArgs,
+  ) -> Result<()> {
+    FlashLoan::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn flash_repay(ctx: Context<FlashRepay>, args: FlashRepayArgs) -> Result<()> {
+    FlashRepay::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    args: InitializeConfigArgs,
+  ) -> Result<()> {
+    InitializeConfig::handle(ctx, args)
+  }
+
   #[access_control(ctx.accounts.validate(&args))]
   pub fn update_fee(ctx: Context<UpdateFee>, args: UpdateFeeArgs) -> Result<()> {
     UpdateFee::handle(ctx, args)
   }
 
   #[access_control(ctx.accounts.validate(&args))]
-  pub fn update_authority(ctx: Context<UpdateAuthority>, args: UpdateAuthorityArgs) -> Result<()> {
-    UpdateAuthority::handle(ctx, args)
+  pub fn update_liquidation_fee(
+    ctx: Context<UpdateLiquidationFee>,
+    args: UpdateLiquidationFeeArgs,
+  ) -> Result<()> {
+    UpdateLiquidationFee::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn propose_authority(
+    ctx: Context<ProposeAuthority>,
+    args: ProposeAuthorityArgs,
+  ) -> Result<()> {
+    ProposeAuthority::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    AcceptAuthority::handle(ctx)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn cancel_authority(ctx: Context<CancelAuthority>) -> Result<()> {
+    CancelAuthority::handle(ctx)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn update_curve_limits(
+    ctx: Context<UpdateCurveLimits>,
+    args: UpdateCurveLimitsArgs,
+  ) -> Result<()> {
+    UpdateCurveLimits::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn update_guardian(ctx: Context<UpdateGuardian>, args: UpdateGuardianArgs) -> Result<()> {
+    UpdateGuardian::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn set_market_pause(ctx: Context<SetMarketPause>, args: SetMarketPauseArgs) -> Result<()> {
+    SetMarketPause::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn migrate_config(ctx: Context<MigrateConfig>, args: MigrateConfigArgs) -> Result<()> {
+    MigrateConfig::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn migrate_market(ctx: Context<MigrateMarket>, args: MigrateMarketArgs) -> Result<()> {
+    MigrateMarket::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn set_fallback_oracle(
+    ctx: Context<SetFallbackOracle>,
+    args: SetFallbackOracleArgs,
+  ) -> Result<()> {
+    SetFallbackOracle::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn update_liquidation_auction(
+    ctx: Context<UpdateLiquidationAuction>,
+    args: UpdateLiquidationAuctionArgs,
+  ) -> Result<()> {
+    UpdateLiquidationAuction::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn update_liquidation_limits(
+    ctx: Context<UpdateLiquidationLimits>,
+    args: UpdateLiquidationLimitsArgs,
+  ) -> Result<()> {
+    UpdateLiquidationLimits::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate())]
+  pub fn update_market_caps(
+    ctx: Context<UpdateMarketCaps>,
+    args: UpdateMarketCapsArgs,
+  ) -> Result<()> {
+    UpdateMarketCaps::handle(ctx, args)
+  }
+
+  #[access_control(ctx.accounts.validate(&args))]
+  pub fn update_pre_liquidation(
+    ctx: Context<UpdatePreLiquidation>,
+    args: UpdatePreLiquidationArgs,
+  ) -> Result<()> {
+    UpdatePreLiquidation::handle(ctx, args)
   }
 
   #[access_control(ctx.accounts.validate(&args))]
@@ -103,4 +272,9 @@ pub mod pathfinder {
     ViewMarket::expected_total_shares(ctx)
   }
 
+  pub fn view_position_health(ctx: Context<ViewPosition>, _owner: Pubkey) -> Result<[u64; 2]> {
+    let health = ViewPosition::expected_health(ctx)?;
+    Ok([health.0, health.1])
+  }
+
 }
@@ -1,8 +1,9 @@
 mod exp;
 mod math;
+mod price;
 mod shares;
 mod tests;
 mod utils;
 mod decimal;
 
-pub use {self::exp::*, self::math::*, self::shares::*, self::utils::*, self::decimal::*};
+pub use {self::exp::*, self::math::*, self::price::*, self::shares::*, self::utils::*, self::decimal::*};
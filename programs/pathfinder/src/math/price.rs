@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::math::*;
+use crate::state::Price;
+
+// Oracle prices are whole quote tokens per whole collateral token, scaled by `price.scale`.
+// Token amounts are in base units, so both mint decimals have to be applied as well.
+
+/// Returns the value of `collateral_amount` in quote base units, rounded down
+pub fn collateral_to_quote_down(
+  collateral_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+) -> Result<u64> {
+  collateral_to_quote(
+    collateral_amount,
+    price,
+    collateral_decimals,
+    quote_decimals,
+    false,
+  )
+}
+
+/// Returns the value of `collateral_amount` in quote base units, rounded up
+pub fn collateral_to_quote_up(
+  collateral_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+) -> Result<u64> {
+  collateral_to_quote(
+    collateral_amount,
+    price,
+    collateral_decimals,
+    quote_decimals,
+    true,
+  )
+}
+
+/// Returns the collateral base units worth `quote_amount`, rounded down
+pub fn quote_to_collateral_down(
+  quote_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+) -> Result<u64> {
+  quote_to_collateral(
+    quote_amount,
+    price,
+    collateral_decimals,
+    quote_decimals,
+    false,
+  )
+}
+
+/// Returns the collateral base units worth `quote_amount`, rounded up
+pub fn quote_to_collateral_up(
+  quote_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+) -> Result<u64> {
+  quote_to_collateral(
+    quote_amount,
+    price,
+    collateral_decimals,
+    quote_decimals,
+    true,
+  )
+}
+
+fn collateral_to_quote(
+  collateral_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+  round_up: bool,
+) -> Result<u64> {
+  // amount * price * 10^quote_decimals / (scale * 10^collateral_decimals)
+  let numerator = U256::from(collateral_amount)
+    .checked_mul(U256::from(price.price))
+    .and_then(|n| n.checked_mul(pow10(quote_decimals)?))
+    .ok_or(MarketError::MathOverflow)?;
+  let denominator = U256::from(price.scale)
+    .checked_mul(pow10(collateral_decimals)?)
+    .ok_or(MarketError::MathOverflow)?;
+
+  div_u256(numerator, denominator, round_up)
+}
+
+fn quote_to_collateral(
+  quote_amount: u64,
+  price: &Price,
+  collateral_decimals: u8,
+  quote_decimals: u8,
+  round_up: bool,
+) -> Result<u64> {
+  // amount * scale * 10^collateral_decimals / (price * 10^quote_decimals)
+  let numerator = U256::from(quote_amount)
+    .checked_mul(U256::from(price.scale))
+    .and_then(|n| n.checked_mul(pow10(collateral_decimals)?))
+    .ok_or(MarketError::MathOverflow)?;
+  let denominator = U256::from(price.price)
+    .checked_mul(pow10(quote_decimals)?)
+    .ok_or(MarketError::MathOverflow)?;
+
+  div_u256(numerator, denominator, round_up)
+}
+
+fn pow10(decimals: u8) -> Option<U256> {
+  // u64 amounts never need more than this
+  if decimals > 38 {
+    return None;
+  }
+  Some(U256::exp10(decimals as usize))
+}
+
+fn div_u256(numerator: U256, denominator: U256, round_up: bool) -> Result<u64> {
+  require!(!denominator.is_zero(), MarketError::MathOverflow);
+
+  let mut result = numerator / denominator;
+  if round_up && !(numerator % denominator).is_zero() {
+    result = result + U256::one();
+  }
+
+  require!(result <= U256::from(u64::MAX), MarketError::MathOverflow);
+
+  Ok(result.as_u64())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use proptest::prelude::*;
+
+  // (collateral decimals, quote decimals)
+  const DECIMAL_PAIRS: [(u8, u8); 4] = [(6, 9), (9, 6), (8, 18), (9, 9)];
+
+  fn price(price: u64, expo: u32) -> Price {
+    Price {
+      price,
+      scale: 10_u64.pow(expo),
+    }
+  }
+
+  #[test]
+  fn test_one_whole_token() {
+    // 1 whole collateral at $2.5 is 2.5 whole quote, whatever the decimals
+    let p = price(2_500_000, 6);
+
+    for (collateral_decimals, quote_decimals) in DECIMAL_PAIRS {
+      let one = 10_u64.pow(collateral_decimals as u32);
+      let expected = 10_u64.pow(quote_decimals as u32) / 10 * 25;
+
+      assert_eq!(
+        collateral_to_quote_down(one, &p, collateral_decimals, quote_decimals).unwrap(),
+        expected
+      );
+      assert_eq!(
+        quote_to_collateral_down(expected, &p, collateral_decimals, quote_decimals).unwrap(),
+        one
+      );
+    }
+  }
+
+  #[test]
+  fn test_overflow() {
+    let p = price(u64::MAX, 0);
+    assert!(collateral_to_quote_down(u64::MAX, &p, 0, 18).is_err());
+    assert!(quote_to_collateral_down(1, &price(0, 9), 9, 9).is_err());
+  }
+
+  proptest! {
+    #[test]
+    fn prop_rounding_brackets(
+      amount in 0..u64::MAX / 1_000,
+      raw_price in 1..1_000_000_000_000u64,
+      expo in 0..12u32,
+      pair in 0..DECIMAL_PAIRS.len(),
+    ) {
+      let (collateral_decimals, quote_decimals) = DECIMAL_PAIRS[pair];
+      let p = price(raw_price, expo);
+
+      if let (Ok(down), Ok(up)) = (
+        collateral_to_quote_down(amount, &p, collateral_decimals, quote_decimals),
+        collateral_to_quote_up(amount, &p, collateral_decimals, quote_decimals),
+      ) {
+        prop_assert!(down <= up);
+        prop_assert!(up - down <= 1);
+      }
+
+      if let (Ok(down), Ok(up)) = (
+        quote_to_collateral_down(amount, &p, collateral_decimals, quote_decimals),
+        quote_to_collateral_up(amount, &p, collateral_decimals, quote_decimals),
+      ) {
+        prop_assert!(down <= up);
+        prop_assert!(up - down <= 1);
+      }
+    }
+
+    #[test]
+    fn prop_round_trip_never_gains(
+      amount in 0..u64::MAX / 1_000,
+      raw_price in 1..1_000_000_000_000u64,
+      expo in 0..12u32,
+      pair in 0..DECIMAL_PAIRS.len(),
+    ) {
+      let (collateral_decimals, quote_decimals) = DECIMAL_PAIRS[pair];
+      let p = price(raw_price, expo);
+
+      if let Ok(quote) = collateral_to_quote_down(amount, &p, collateral_decimals, quote_decimals) {
+        let back = quote_to_collateral_down(quote, &p, collateral_decimals, quote_decimals).unwrap();
+        prop_assert!(back <= amount);
+      }
+
+      if let Ok(collateral) = quote_to_collateral_down(amount, &p, collateral_decimals, quote_decimals) {
+        let back = collateral_to_quote_down(collateral, &p, collateral_decimals, quote_decimals).unwrap();
+        prop_assert!(back <= amount);
+      }
+    }
+
+    #[test]
+    fn prop_monotonic_in_amount(
+      amount in 0..u64::MAX / 1_000,
+      extra in 0..1_000_000u64,
+      raw_price in 1..1_000_000_000_000u64,
+      expo in 0..12u32,
+      pair in 0..DECIMAL_PAIRS.len(),
+    ) {
+      let (collateral_decimals, quote_decimals) = DECIMAL_PAIRS[pair];
+      let p = price(raw_price, expo);
+
+      if let (Ok(low), Ok(high)) = (
+        collateral_to_quote_down(amount, &p, collateral_decimals, quote_decimals),
+        collateral_to_quote_down(amount + extra, &p, collateral_decimals, quote_decimals),
+      ) {
+        prop_assert!(low <= high);
+      }
+    }
+
+    #[test]
+    fn prop_decimals_only_rescale(
+      whole in 0..1_000_000u64,
+      raw_price in 1..1_000_000_000u64,
+      expo in 0..9u32,
+      pair in 0..DECIMAL_PAIRS.len(),
+    ) {
+      // the same whole-token position has the same whole-token value under any decimals
+      let (collateral_decimals, quote_decimals) = DECIMAL_PAIRS[pair];
+      let p = price(raw_price, expo);
+
+      let amount = whole * 10_u64.pow(collateral_decimals as u32);
+      let reference = collateral_to_quote_down(whole, &p, 0, 0).unwrap();
+
+      if let Ok(value) = collateral_to_quote_down(amount, &p, collateral_decimals, quote_decimals) {
+        prop_assert_eq!(value / 10_u64.pow(quote_decimals as u32), reference);
+      }
+    }
+  }
+}
@@ -1,5 +1,5 @@
 use crate::math::*;
-use crate::MAX_RATE_AT_TARGET;
+use crate::{linear_kink_rate, MAX_RATE_AT_TARGET};
 
 #[cfg(test)]
 mod tests {
@@ -193,4 +193,18 @@ mod tests {
     let result = w_exp(WEXP_UPPER_BOUND).unwrap();
     assert_eq!(result, WEXP_UPPER_VALUE);
   }
+
+  #[test]
+  fn test_linear_kink_rate() {
+    let kink = 8 * WAD_INT / 10;
+
+    // Below the kink only the low slope applies
+    assert_eq!(linear_kink_rate(0, 1, 100, 1_000, kink).unwrap(), 1);
+    assert_eq!(linear_kink_rate(kink / 2, 1, 100, 1_000, kink).unwrap(), 51);
+    assert_eq!(linear_kink_rate(kink, 1, 100, 1_000, kink).unwrap(), 101);
+
+    // Above the kink the high slope applies to the excess utilization
+    assert_eq!(linear_kink_rate(9 * WAD_INT / 10, 1, 100, 1_000, kink).unwrap(), 601);
+    assert_eq!(linear_kink_rate(WAD_INT, 1, 100, 1_000, kink).unwrap(), 1_101);
+  }
 }
\ No newline at end of file
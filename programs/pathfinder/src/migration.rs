@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::MarketError;
+
+/// Decodes an account still on its version 0 layout `T`. Accounts on a versioned
+/// layout already span `space` bytes and are rejected.
+pub fn load_legacy<T: AnchorDeserialize>(
+  account: &AccountInfo,
+  discriminator: &[u8],
+  space: usize,
+) -> Result<T> {
+  let data = account.try_borrow_data()?;
+
+  require!(
+    data.len() >= 8 && &data[..8] == discriminator,
+    ErrorCode::AccountDiscriminatorMismatch
+  );
+  require_gt!(space, data.len(), MarketError::AlreadyMigrated);
+
+  T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+/// Grows `account` to `space`, topping up its rent from `payer`, and writes the
+/// upgraded layout over it.
+pub fn write_upgraded<'info, T: AccountSerialize>(
+  account: &AccountInfo<'info>,
+  payer: &Signer<'info>,
+  system_program: &Program<'info, System>,
+  space: usize,
+  upgraded: &T,
+) -> Result<()> {
+  let rent = Rent::get()?.minimum_balance(space);
+  let top_up = rent.saturating_sub(account.lamports());
+
+  if top_up > 0 {
+    transfer(
+      CpiContext::new(
+        system_program.to_account_info(),
+        Transfer {
+          from: payer.to_account_info(),
+          to: account.clone(),
+        },
+      ),
+      top_up,
+    )?;
+  }
+
+  account.realloc(space, true)?;
+
+  let mut data = account.try_borrow_mut_data()?;
+  upgraded.try_serialize(&mut &mut data[..])?;
+
+  Ok(())
+}
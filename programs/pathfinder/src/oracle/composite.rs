@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::oracle::{bound_price, pyth::oracle_pyth_get_price, switchboard::oracle_sb_get_price};
+use crate::state::oracle::{FeedSource, Oracle, OracleConfig, OracleSource, Price};
+
+pub fn oracle_composite_init(
+  source: &OracleSource,
+  oracle_id: &Pubkey,
+  quote_oracle_id: &Pubkey,
+  config: &OracleConfig,
+) -> Result<Oracle> {
+  require!(
+    *quote_oracle_id != Pubkey::default() && quote_oracle_id != oracle_id,
+    MarketError::InvalidOracleId
+  );
+
+  Ok(Oracle {
+    id: oracle_id.clone(),
+    source: source.clone(),
+    config: *config,
+    quote_id: quote_oracle_id.clone(),
+    fallback: None,
+  })
+}
+
+fn feed_get_price(
+  feed: FeedSource,
+  feed_id: &Pubkey,
+  config: &OracleConfig,
+  ai: &AccountInfo,
+  upper_bound: bool,
+) -> Result<Price> {
+  let oracle = Oracle {
+    id: *feed_id,
+    source: feed.into(),
+    config: *config,
+    quote_id: Pubkey::default(),
+    fallback: None,
+  };
+
+  let oracle_price = match feed {
+    FeedSource::PythPull => oracle_pyth_get_price(&oracle, ai)?,
+    FeedSource::SwitchboardPull => oracle_sb_get_price(&oracle, ai)?,
+  };
+
+  bound_price(&oracle_price, config, upper_bound)
+}
+
+pub fn oracle_composite_get_price(
+  oracle: &Oracle,
+  base: FeedSource,
+  quote: FeedSource,
+  base_ai: &AccountInfo,
+  quote_ai: &AccountInfo,
+  upper_bound: bool,
+) -> Result<Price> {
+  // the quote bound is taken on the opposite side so the ratio stays conservative
+  let base_price = feed_get_price(base, &oracle.id, &oracle.config, base_ai, upper_bound)?;
+  let quote_price = feed_get_price(
+    quote,
+    &oracle.quote_id,
+    &oracle.config,
+    quote_ai,
+    !upper_bound,
+  )?;
+
+  // base / quote, kept in the base feed scale
+  let numerator = (base_price.price as u128)
+    .checked_mul(quote_price.scale as u128)
+    .ok_or(MarketError::MathOverflow)?;
+  let denominator = quote_price.price as u128;
+
+  let price = if upper_bound {
+    numerator
+      .checked_add(denominator - 1)
+      .ok_or(MarketError::MathOverflow)?
+      / denominator
+  } else {
+    numerator / denominator
+  };
+
+  // a base price far below the quote price can truncate to zero
+  require!(price > 0, MarketError::OraclePriceNotPositive);
+
+  Ok(Price {
+    price: u64::try_from(price).map_err(|_| MarketError::MathOverflow)?,
+    scale: base_price.scale,
+  })
+}
@@ -1,24 +1,286 @@
+use crate::error::MarketError;
+use crate::math::{max_u64, mul_div_up, U256};
 use crate::oracle::{
-  pyth::{oracle_pyth_get_price, oracle_pyth_init},
+  composite::{oracle_composite_get_price, oracle_composite_init},
+  pyth::{load_price_update_v2_checked, oracle_pyth_get_price, oracle_pyth_init},
   switchboard::{oracle_sb_get_price, oracle_sb_init},
 };
-use crate::state::oracle::{Oracle, OracleSource, Price};
+use crate::state::oracle::{
+  FallbackOracle, FeedSource, Oracle, OracleConfig, OraclePrice, OracleSource, Price,
+};
+use crate::state::{BPS, HR_SECONDS};
 use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::error::GetPriceError;
 
+pub mod composite;
 pub mod pyth;
 pub mod switchboard;
 
+// Feed accounts passed to an instruction, only the primary is always required
+pub struct OracleAccounts<'a, 'info> {
+  pub oracle_ai: &'a AccountInfo<'info>,
+  pub quote_oracle_ai: Option<&'a AccountInfo<'info>>,
+  pub fallback_oracle_ai: Option<&'a AccountInfo<'info>>,
+}
+
 // Base trait that defines both the required data and behavior
-pub fn oracle_init(source: &OracleSource, oracle_id: &Pubkey) -> Result<Oracle> {
-  match source {
-    OracleSource::PythPull => Ok(oracle_pyth_init(source, oracle_id)?),
-    OracleSource::SwitchboardPull => Ok(oracle_sb_init(source, oracle_id)?),
+pub fn oracle_init(
+  source: &OracleSource,
+  oracle_id: &Pubkey,
+  quote_oracle_id: &Pubkey,
+  config: &OracleConfig,
+  fallback: Option<FallbackOracle>,
+) -> Result<Oracle> {
+  validate_oracle_config(source, config)?;
+
+  let mut oracle = match source {
+    OracleSource::PythPull => oracle_pyth_init(source, oracle_id, config)?,
+    OracleSource::SwitchboardPull => oracle_sb_init(source, oracle_id, config)?,
+    OracleSource::Composite { .. } => {
+      oracle_composite_init(source, oracle_id, quote_oracle_id, config)?
+    }
+  };
+
+  if let Some(fallback) = &fallback {
+    validate_fallback(&oracle, fallback)?;
+  }
+  oracle.fallback = fallback;
+
+  Ok(oracle)
+}
+
+pub fn validate_fallback(oracle: &Oracle, fallback: &FallbackOracle) -> Result<()> {
+  require!(
+    fallback.id != Pubkey::default() && fallback.id != oracle.id,
+    MarketError::InvalidFallbackOracle
+  );
+  require!(
+    fallback.max_deviation_bps > 0 && fallback.max_deviation_bps <= BPS,
+    MarketError::InvalidFallbackOracle
+  );
+
+  // the shared config has to cover the fallback source as well
+  validate_oracle_config(&fallback.source.into(), &oracle.config)
+}
+
+pub fn validate_oracle_config(source: &OracleSource, config: &OracleConfig) -> Result<()> {
+  // an hour is the most we ever tolerate
+  require!(
+    config.max_age > 0 && config.max_age <= HR_SECONDS,
+    MarketError::InvalidOracleConfig
+  );
+  require!(
+    config.max_conf_bps > 0 && config.max_conf_bps <= BPS,
+    MarketError::InvalidOracleConfig
+  );
+  require!(
+    config.price_floor_bps > 0 && config.price_floor_bps <= BPS,
+    MarketError::InvalidOracleConfig
+  );
+
+  let uses_switchboard = match source {
+    OracleSource::PythPull => false,
+    OracleSource::SwitchboardPull => true,
+    OracleSource::Composite { base, quote } => {
+      *base == FeedSource::SwitchboardPull || *quote == FeedSource::SwitchboardPull
+    }
+  };
+
+  if uses_switchboard {
+    require!(
+      config.min_sb_responses > 0,
+      MarketError::InvalidOracleConfig
+    );
+  }
+
+  Ok(())
+}
+
+pub fn validate_confidence(config: &OracleConfig, price: u64, conf: u64) -> Result<()> {
+  let max_conf = (price as u128)
+    .checked_mul(config.max_conf_bps as u128)
+    .ok_or(MarketError::MathOverflow)?
+    / BPS as u128;
+
+  require!(
+    conf as u128 <= max_conf,
+    MarketError::OracleConfidenceTooWide
+  );
+
+  Ok(())
+}
+
+pub fn validated_price(
+  config: &OracleConfig,
+  price: i128,
+  conf: u64,
+  scale: u64,
+) -> Result<OraclePrice> {
+  require!(price > 0, MarketError::OraclePriceNotPositive);
+
+  let price = u64::try_from(price).map_err(|_| MarketError::MathOverflow)?;
+
+  validate_confidence(config, price, conf)?;
+
+  Ok(OraclePrice { price, conf, scale })
+}
+
+pub fn bound_price(
+  oracle_price: &OraclePrice,
+  config: &OracleConfig,
+  upper_bound: bool,
+) -> Result<Price> {
+  let price = if upper_bound {
+    oracle_price
+      .price
+      .checked_add(oracle_price.conf)
+      .ok_or(MarketError::MathOverflow)?
+  } else {
+    // saturate at the floor rather than reverting on a wide interval
+    let floor = mul_div_up(
+      oracle_price.price as u128,
+      config.price_floor_bps as u128,
+      BPS as u128,
+    )?;
+    max_u64(oracle_price.price.saturating_sub(oracle_price.conf), floor)
+  };
+
+  require!(price > 0, MarketError::OraclePriceNotPositive);
+
+  Ok(Price {
+    price,
+    scale: oracle_price.scale,
+  })
+}
+
+pub fn oracle_get_price(
+  oracle: &Oracle,
+  accounts: &OracleAccounts,
+  upper_bound: bool,
+) -> Result<Price> {
+  let primary = feed_get_price(
+    oracle,
+    accounts.oracle_ai,
+    accounts.quote_oracle_ai,
+    upper_bound,
+  );
+
+  let fallback = match &oracle.fallback {
+    Some(fallback) => fallback,
+    None => return primary,
+  };
+
+  let fallback_ai = accounts
+    .fallback_oracle_ai
+    .ok_or(MarketError::InvalidOracleAccount)?;
+  check_fallback_account(fallback, fallback_ai)?;
+
+  let fallback_oracle = Oracle {
+    id: fallback.id,
+    source: fallback.source.into(),
+    config: oracle.config,
+    quote_id: Pubkey::default(),
+    fallback: None,
+  };
+  let fallback_price = feed_get_price(&fallback_oracle, fallback_ai, None, upper_bound);
+
+  match primary {
+    Ok(primary_price) => {
+      match fallback_price {
+        Ok(fallback_price) => {
+          validate_deviation(&primary_price, &fallback_price, fallback.max_deviation_bps)?
+        }
+        // a stale or uncertain fallback doesn't block the primary
+        Err(err) if is_failover_error(&err) => {}
+        Err(err) => return Err(err),
+      }
+      Ok(primary_price)
+    }
+    Err(err) if is_failover_error(&err) => {
+      msg!("primary oracle unavailable, using fallback");
+      fallback_price
+    }
+    Err(err) => Err(err),
   }
 }
 
-pub fn oracle_get_price(oracle: &Oracle, ai: &AccountInfo, upper_bound: bool) -> Result<Price> {
+// The fallback account is picked by the caller, it has to hold the configured feed
+fn check_fallback_account(fallback: &FallbackOracle, ai: &AccountInfo) -> Result<()> {
+  let matches = match fallback.source {
+    FeedSource::PythPull => {
+      load_price_update_v2_checked(ai)?.price_message.feed_id == fallback.id.to_bytes()
+    }
+    FeedSource::SwitchboardPull => ai.key() == fallback.id,
+  };
+  require!(matches, MarketError::InvalidOracleAccount);
+
+  Ok(())
+}
+
+// Stale or too uncertain readings fail over, anything else is a misconfiguration
+fn is_failover_error(err: &Error) -> bool {
+  let code = match err {
+    Error::AnchorError(err) => err.error_code_number,
+    Error::ProgramError(_) => return false,
+  };
+
+  [
+    MarketError::StaleOracle,
+    MarketError::OracleConfidenceTooWide,
+    MarketError::InsufficientOracleResponses,
+  ]
+  .into_iter()
+  .map(u32::from)
+  .chain([u32::from(GetPriceError::PriceTooOld)])
+  .any(|failover_code| failover_code == code)
+}
+
+pub fn validate_deviation(a: &Price, b: &Price, max_deviation_bps: u64) -> Result<()> {
+  // compare a.price / a.scale with b.price / b.scale
+  let a_value = U256::from(a.price) * U256::from(b.scale);
+  let b_value = U256::from(b.price) * U256::from(a.scale);
+
+  let difference = if a_value > b_value {
+    a_value - b_value
+  } else {
+    b_value - a_value
+  };
+
+  require!(
+    difference * U256::from(BPS) <= a_value * U256::from(max_deviation_bps),
+    MarketError::OracleDeviationTooHigh
+  );
+
+  Ok(())
+}
+
+fn feed_get_price(
+  oracle: &Oracle,
+  ai: &AccountInfo,
+  quote_ai: Option<&AccountInfo>,
+  upper_bound: bool,
+) -> Result<Price> {
   match oracle.source {
-    OracleSource::PythPull => Ok(oracle_pyth_get_price(oracle, ai, upper_bound)?),
-    OracleSource::SwitchboardPull => Ok(oracle_sb_get_price(oracle, ai)?),
+    OracleSource::PythPull => bound_price(
+      &oracle_pyth_get_price(oracle, ai)?,
+      &oracle.config,
+      upper_bound,
+    ),
+    OracleSource::SwitchboardPull => bound_price(
+      &oracle_sb_get_price(oracle, ai)?,
+      &oracle.config,
+      upper_bound,
+    ),
+    OracleSource::Composite { base, quote } => {
+      let quote_ai = quote_ai.ok_or(MarketError::InvalidOracleAccount)?;
+      Ok(oracle_composite_get_price(
+        oracle,
+        base,
+        quote,
+        ai,
+        quote_ai,
+        upper_bound,
+      )?)
+    }
   }
 }
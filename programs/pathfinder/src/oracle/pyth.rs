@@ -1,24 +1,43 @@
 use anchor_lang::prelude::*;
 
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, FeedId, PriceUpdateV2};
+use pyth_solana_receiver_sdk::price_update::{
+  get_feed_id_from_hex, FeedId, PriceUpdateV2, VerificationLevel,
+};
 
 use crate::error::MarketError;
-use crate::state::HR_SECONDS;
 
-use crate::oracle::OracleSource;
-use crate::state::oracle::{Oracle, Price};
+use crate::oracle::{validated_price, OracleSource};
+use crate::state::oracle::{Oracle, OracleConfig, OraclePrice, PythVerificationLevel};
 
-pub fn oracle_pyth_init(source: &OracleSource, oracle_id: &Pubkey) -> Result<Oracle> {
+pub fn oracle_pyth_init(
+  source: &OracleSource,
+  oracle_id: &Pubkey,
+  config: &OracleConfig,
+) -> Result<Oracle> {
   Ok(Oracle {
     id: oracle_id.clone(),
     source: source.clone(),
+    config: *config,
+    quote_id: Pubkey::default(),
+    fallback: None,
   })
 }
 
+impl From<PythVerificationLevel> for VerificationLevel {
+  fn from(level: PythVerificationLevel) -> Self {
+    match level {
+      PythVerificationLevel::Partial { num_signatures } => {
+        VerificationLevel::Partial { num_signatures }
+      }
+      PythVerificationLevel::Full => VerificationLevel::Full,
+    }
+  }
+}
+
 pub fn load_price_update_v2_checked(ai: &AccountInfo) -> Result<PriceUpdateV2> {
   require!(
     ai.owner.eq(&pyth_solana_receiver_sdk::id()),
-    MarketError::InvalidOracle
+    MarketError::InvalidOracleOwner
   );
 
   let price_feed_data = ai.try_borrow_data()?;
@@ -26,7 +45,7 @@ pub fn load_price_update_v2_checked(ai: &AccountInfo) -> Result<PriceUpdateV2> {
 
   require!(
     discriminator == <PriceUpdateV2 as anchor_lang::Discriminator>::DISCRIMINATOR,
-    MarketError::InvalidOracle
+    MarketError::InvalidOracleData
   );
 
   Ok(PriceUpdateV2::deserialize(
@@ -38,36 +57,30 @@ pub fn oracle_pyth_feed_id(oracle_id: &Pubkey) -> Result<FeedId> {
   Ok(oracle_id.to_bytes())
 }
 
-pub fn oracle_pyth_get_price(
-  oracle: &Oracle,
-  ai: &AccountInfo,
-  upper_bound: bool,
-) -> Result<Price> {
+pub fn oracle_pyth_get_price(oracle: &Oracle, ai: &AccountInfo) -> Result<OraclePrice> {
   let feed_id = oracle_pyth_feed_id(&oracle.id)?;
 
   let price_update = load_price_update_v2_checked(ai)?;
 
   let clock = Clock::get()?;
-  let price_feed = price_update.get_price_no_older_than(&clock, HR_SECONDS, &feed_id)?;
+  let price_feed = price_update.get_price_no_older_than_with_custom_verification_level(
+    &clock,
+    oracle.config.max_age,
+    &feed_id,
+    oracle.config.pyth_verification_level.into(),
+  )?;
+
+  // scale is derived from a non-positive exponent only
+  require!(price_feed.exponent <= 0, MarketError::InvalidOracleData);
 
   let price_precision = 10_u64
     .checked_pow(price_feed.exponent.unsigned_abs())
     .ok_or(MarketError::MathOverflow)?;
 
-  let price = price_feed.price as u64;
-
-  let adjusted_price = if upper_bound {
-    price
-      .checked_add(price_feed.conf as u64)
-      .ok_or(MarketError::MathOverflow)?
-  } else {
-    price
-      .checked_sub(price_feed.conf as u64)
-      .ok_or(MarketError::MathUnderflow)?
-  };
-
-  Ok(Price {
-    price: adjusted_price,
-    scale: price_precision,
-  })
-}
\ No newline at end of file
+  validated_price(
+    &oracle.config,
+    price_feed.price as i128,
+    price_feed.conf,
+    price_precision,
+  )
+}
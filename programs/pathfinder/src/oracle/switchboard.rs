@@ -6,16 +6,23 @@ use std::mem;
 use switchboard_on_demand::{PullFeedAccountData, ID as SB_ID, SB_ON_DEMAND_PRECISION};
 
 use crate::error::MarketError;
-use crate::oracle::OracleSource;
+use crate::oracle::{validated_price, OracleSource};
 use crate::state::{
-  constants::{HR_MILLISECONDS, PRICE_PRECISION, SLOT_IN_MILLISECONDS},
-  oracle::{Oracle, Price},
+  constants::{PRICE_PRECISION, SLOT_IN_MILLISECONDS},
+  oracle::{Oracle, OracleConfig, OraclePrice},
 };
 
-pub fn oracle_sb_init(source: &OracleSource, oracle_id: &Pubkey) -> Result<Oracle> {
+pub fn oracle_sb_init(
+  source: &OracleSource,
+  oracle_id: &Pubkey,
+  config: &OracleConfig,
+) -> Result<Oracle> {
   Ok(Oracle {
     id: oracle_id.clone(),
     source: source.clone(),
+    config: *config,
+    quote_id: Pubkey::default(),
+    fallback: None,
   })
 }
 
@@ -23,9 +30,9 @@ pub fn load_ref<'a, T: ZeroCopy + Owner>(
   ai: &'a AccountInfo,
   oracle_id: &Pubkey,
 ) -> Result<Ref<'a, T>> {
-  require!(ai.owner.eq(&SB_ID), MarketError::InvalidOracle);
+  require!(ai.owner.eq(&SB_ID), MarketError::InvalidOracleOwner);
 
-  require!(ai.key.eq(oracle_id), MarketError::InvalidOracle);
+  require!(ai.key.eq(oracle_id), MarketError::InvalidOracleAccount);
 
   let data = ai.try_borrow_data()?;
   if data.len() < T::discriminator().len() {
@@ -49,31 +56,60 @@ fn convert_sb_i128(switchboard_i128: &i128) -> Result<i128> {
     .ok_or(error!(MarketError::MathOverflow))
 }
 
-pub fn oracle_sb_get_price(oracle: &Oracle, ai: &AccountInfo) -> Result<Price> {
-  let pull_feed_account_info: Ref<PullFeedAccountData> = load_ref(ai, &oracle.id)?;
+pub fn oracle_sb_get_price(oracle: &Oracle, ai: &AccountInfo) -> Result<OraclePrice> {
+  let feed: Ref<PullFeedAccountData> = load_ref(ai, &oracle.id)?;
+
+  // the feed itself has to demand at least as many responses as the market
+  require!(
+    feed.min_responses >= oracle.config.min_sb_responses,
+    MarketError::InsufficientOracleResponses
+  );
 
-  let price_i128 = pull_feed_account_info.result.value().unwrap();
-  let price = u64::try_from(convert_sb_i128(&price_i128)?).unwrap();
+  let price_i128 = feed.median_value().ok_or(MarketError::InvalidOracleData)?;
+  let std_dev_i128 = feed.std_dev().ok_or(MarketError::InvalidOracleData)?;
 
-  let latest_oracle_submssions: Vec<switchboard_on_demand::OracleSubmission> =
-    pull_feed_account_info.latest_submissions();
+  let max_age_slots = oracle
+    .config
+    .max_age
+    .checked_mul(1_000)
+    .ok_or(MarketError::MathOverflow)?
+    / SLOT_IN_MILLISECONDS;
 
-  let delay = Clock::get()?
-    .slot
-    .checked_sub(latest_oracle_submssions[0].landed_at)
-    .ok_or(error!(MarketError::MathOverflow))?;
+  let current_slot = Clock::get()?.slot;
 
-  // TODO: Check if this is correct
+  // median_result_land_slot indexes submissions directly, guard it first
   require!(
-    delay
-      .checked_mul(SLOT_IN_MILLISECONDS)
-      .ok_or(error!(MarketError::MathOverflow))?
-      <= HR_MILLISECONDS,
-    MarketError::StaleOracle
+    (feed.result.submission_idx as usize) < feed.submissions.len(),
+    MarketError::InvalidOracleData
   );
 
-  Ok(Price {
-    price,
-    scale: PRICE_PRECISION as u64, // 1e9 scale
-  })
-}
\ No newline at end of file
+  let delay = current_slot
+    .checked_sub(feed.median_result_land_slot())
+    .ok_or(MarketError::InvalidOracleData)?;
+
+  require!(delay <= max_age_slots, MarketError::StaleOracle);
+
+  let live_submissions = feed
+    .submissions
+    .iter()
+    .filter(|submission| {
+      !submission.is_empty() && current_slot.saturating_sub(submission.landed_at) <= max_age_slots
+    })
+    .count();
+
+  require!(
+    live_submissions as u64 >= oracle.config.min_sb_responses as u64,
+    MarketError::InsufficientOracleResponses
+  );
+
+  let std_dev =
+    u64::try_from(convert_sb_i128(&std_dev_i128)?).map_err(|_| MarketError::InvalidOracleData)?;
+
+  // std dev of the samples stands in for a confidence interval
+  validated_price(
+    &oracle.config,
+    convert_sb_i128(&price_i128)?,
+    std_dev,
+    PRICE_PRECISION as u64, // 1e9 scale
+  )
+}
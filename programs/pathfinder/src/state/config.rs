@@ -1,9 +1,24 @@
 use anchor_lang::prelude::*;
 
+use crate::state::constants::CONFIG_RESERVED_BYTES;
+use crate::state::irm::AdaptiveCurveLimits;
+
 #[account]
+#[derive(InitSpace)]
 pub struct Config {
   pub bump: u8,
+  pub version: u8,
   pub authority: Pubkey,
+  // proposed authority, becomes the authority once it accepts
+  pub pending_authority: Pubkey,
+  // may pause markets alongside the authority
+  pub guardian: Pubkey,
   pub fee_factor: u64,
   pub fee_recipient: Pubkey,
-}
\ No newline at end of file
+  pub curve_limits: AdaptiveCurveLimits,
+  // protocol cut of the liquidation incentive, paid to fee_recipient as lender shares
+  pub liquidation_fee_factor: u64,
+
+  // zeroed, new fields are carved out of it and announced by a version bump
+  pub reserved: [u8; CONFIG_RESERVED_BYTES],
+}
@@ -3,16 +3,32 @@ pub const DELEGATE_SEED_PREFIX: &[u8] = b"delegate";
 pub const CONFIG_SEED_PREFIX: &[u8] = b"config";
 pub const MARKET_SHARES_SEED_PREFIX: &[u8] = b"lender_shares";
 pub const BORROWER_SHARES_SEED_PREFIX: &[u8] = b"borrower_shares";
+pub const PRE_LIQUIDATION_SEED_PREFIX: &[u8] = b"pre_liquidation";
+pub const LIQUIDATION_STATE_SEED_PREFIX: &[u8] = b"liquidation_state";
 
 // 0.3 * 1e18
 pub const LIQUIDATION_CURSOR: u64 = 300_000_000_000_000_000;
 // 1.15 * 1e18
 pub const MAX_LIQUIDATION_INCENTIVE_FACTOR: u64 = 1_150_000_000_000_000_000;
+// longest ramp of the liquidation auction incentive
+pub const MAX_LIQUIDATION_AUCTION_DURATION: u64 = 24 * HR_SECONDS;
 
 pub const HR_SECONDS: u64 = 3600; // 1 hour
 pub const HR_MILLISECONDS: u64 = 3_600_000;
 pub const SLOT_IN_MILLISECONDS: u64 = 400;
 
+pub const BPS: u64 = 10_000;
+
 pub const MAX_FEE_FACTOR: u64 = 100_000_000_000_000_000; // 10% in WAD (0.1 * 1e18)
+pub const MAX_LIQUIDATION_FEE_FACTOR: u64 = 500_000_000_000_000_000; // 50% of the incentive in WAD
+
+pub const PRICE_PRECISION: u128 = 1_000_000_000; //expo = -9;
+
+pub const LTV_PRECISION: u128 = 1_000_000_000; // scale of the borrow and liquidation ltvs
+
+// layout versions, accounts created before versioning are version 0
+pub const MARKET_VERSION: u8 = 1;
+pub const CONFIG_VERSION: u8 = 1;
 
-pub const PRICE_PRECISION: u128 = 1_000_000_000; //expo = -9;
\ No newline at end of file
+pub const MARKET_RESERVED_BYTES: usize = 40;
+pub const CONFIG_RESERVED_BYTES: usize = 56;
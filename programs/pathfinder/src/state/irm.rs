@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+// Rates are per second and utilizations are fractions, both scaled by WAD
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, InitSpace)]
+pub enum IrmKind {
+  // rate at target adapts to keep utilization near the target
+  Adaptive { curve: AdaptiveCurve },
+  // constant borrow rate regardless of utilization
+  Fixed { rate: u64 },
+  // base_rate + slope_low up to kink_utilization, then slope_high up to full utilization
+  LinearKink {
+    base_rate: u64,
+    slope_low: u64,
+    slope_high: u64,
+    kink_utilization: u64,
+  },
+}
+
+// Parameters of the adaptive curve, all scaled by WAD
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, InitSpace)]
+pub struct AdaptiveCurve {
+  pub target_utilization: u64,
+  pub curve_steepness: u64,
+  // per second
+  pub adjustment_speed: u64,
+  // per second
+  pub min_rate_at_target: u64,
+  // per second
+  pub max_rate_at_target: u64,
+}
+
+// Protocol bounds on the adaptive curve of new markets, all scaled by WAD
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct AdaptiveCurveLimits {
+  pub min_target_utilization: u64,
+  pub max_target_utilization: u64,
+  pub max_curve_steepness: u64,
+  // per second
+  pub max_adjustment_speed: u64,
+  // per second
+  pub min_rate_at_target: u64,
+  // per second
+  pub max_rate_at_target: u64,
+}
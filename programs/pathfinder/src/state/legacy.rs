@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MarketError;
+use crate::math::mul_div_down;
+use crate::state::{
+  AdaptiveCurveLimits, Config, FeedSource, IrmKind, LiquidationLimits, Market, MarketPause, Oracle,
+  PreLiquidationConfig, CONFIG_RESERVED_BYTES, CONFIG_VERSION, LTV_PRECISION,
+  MARKET_RESERVED_BYTES, MARKET_VERSION,
+};
+
+// Layouts of accounts created before versioning (version 0). They are only
+// decoded by the migrate instructions, never written back.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyOracle {
+  pub id: Pubkey,
+  pub source: FeedSource,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyMarket {
+  pub bump: u8,
+
+  // deposits
+  pub deposit_index: u128,
+  pub total_shares: u64,
+  pub quote_mint: Pubkey,
+  pub quote_mint_decimals: u8,
+
+  // borrows
+  pub borrow_index: u128,
+  pub total_borrow_shares: u64,
+  pub total_collateral: u64,
+  pub collateral_mint: Pubkey,
+  pub collateral_mint_decimals: u8,
+  pub ltv_factor: u64,
+
+  // accounting
+  pub oracle: LegacyOracle,
+  pub rate_at_target: u128,
+  pub last_accrual_timestamp: u64,
+  pub fee_shares: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyConfig {
+  pub bump: u8,
+  pub authority: Pubkey,
+  pub fee_factor: u64,
+  pub fee_recipient: Pubkey,
+}
+
+impl LegacyMarket {
+  /// The legacy ltv factor scaled debt by the quote decimals, a factor of
+  /// 0.8 * 10^quote_decimals meant 80%. Converted to LTV_PRECISION, rounded down.
+  pub fn max_borrow_ltv(&self) -> Result<u64> {
+    mul_div_down(
+      self.ltv_factor as u128,
+      LTV_PRECISION,
+      10_u128
+        .checked_pow(self.quote_mint_decimals as u32)
+        .ok_or(MarketError::MathOverflow)?,
+    )
+  }
+
+  /// Carries the balances over and fills the fields added since with the given
+  /// risk settings, or their neutral value: uncapped, unpaused, no bad debt.
+  /// The address stays derived from the legacy ltv factor.
+  pub fn upgrade(
+    self,
+    oracle: Oracle,
+    irm: IrmKind,
+    max_borrow_ltv: u64,
+    liquidation_ltv: u64,
+  ) -> Market {
+    Market {
+      bump: self.bump,
+      version: MARKET_VERSION,
+
+      deposit_index: self.deposit_index,
+      total_shares: self.total_shares,
+      quote_mint: self.quote_mint,
+      quote_mint_decimals: self.quote_mint_decimals,
+
+      borrow_index: self.borrow_index,
+      total_borrow_shares: self.total_borrow_shares,
+      total_collateral: self.total_collateral,
+      collateral_mint: self.collateral_mint,
+      collateral_mint_decimals: self.collateral_mint_decimals,
+      max_borrow_ltv,
+      liquidation_ltv,
+      pre_liquidation: PreLiquidationConfig::default(),
+      liquidation_auction_duration: 0,
+      liquidation_limits: LiquidationLimits::default(),
+
+      oracle,
+      irm,
+      rate_at_target: self.rate_at_target,
+      last_accrual_timestamp: self.last_accrual_timestamp,
+      fee_shares: self.fee_shares,
+      bad_debt: 0,
+
+      supply_cap: 0,
+      borrow_cap: 0,
+
+      paused: MarketPause::default(),
+      seed_ltv: self.ltv_factor,
+      reserved: [0; MARKET_RESERVED_BYTES],
+    }
+  }
+}
+
+impl LegacyConfig {
+  /// Carries the roles and fees over, no pending authority or guardian is set.
+  pub fn upgrade(self, curve_limits: AdaptiveCurveLimits) -> Config {
+    Config {
+      bump: self.bump,
+      version: CONFIG_VERSION,
+      authority: self.authority,
+      pending_authority: Pubkey::default(),
+      guardian: Pubkey::default(),
+      fee_factor: self.fee_factor,
+      liquidation_fee_factor: 0,
+      fee_recipient: self.fee_recipient,
+      curve_limits,
+      reserved: [0; CONFIG_RESERVED_BYTES],
+    }
+  }
+}
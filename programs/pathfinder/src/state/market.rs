@@ -1,11 +1,16 @@
 use anchor_lang::prelude::*;
 
+use crate::error::MarketError;
+use crate::state::constants::MARKET_RESERVED_BYTES;
+use crate::state::irm::IrmKind;
 use crate::state::oracle::Oracle;
 use crate::math::*;
 
 #[account]
+#[derive(InitSpace)]
 pub struct Market {
   pub bump: u8,
+  pub version: u8,
 
   // deposits
   pub deposit_index: u128,
@@ -19,13 +24,79 @@ pub struct Market {
   pub total_collateral: u64,
   pub collateral_mint: Pubkey,
   pub collateral_mint_decimals: u8,
-  pub ltv_factor: u64,
+  pub max_borrow_ltv: u64,
 
   // accounting
   pub oracle: Oracle,
+  pub irm: IrmKind,
   pub rate_at_target: u128,
   pub last_accrual_timestamp: u64,
   pub fee_shares: u64,
+  pub bad_debt: u64,
+
+  // limits on total assets, zero means uncapped
+  pub supply_cap: u64,
+  pub borrow_cap: u64,
+
+  // emergency stops, set by the guardian or the authority
+  pub paused: MarketPause,
+
+  // ltv the address is derived from, migrated markets keep their legacy ltv factor.
+  // Zero on markets created before it was stored, see seed_ltv_bytes
+  pub seed_ltv: u64,
+  // above max_borrow_ltv, positions past it can be liquidated. Zero falls back
+  // to max_borrow_ltv, see effective_liquidation_ltv
+  pub liquidation_ltv: u64,
+  // band below liquidation_ltv where opted in positions can be partially liquidated
+  pub pre_liquidation: PreLiquidationConfig,
+  // seconds for the liquidation incentive to ramp up to its cap, zero pays the cap at once
+  pub liquidation_auction_duration: u64,
+  // bounds on how much of a position one liquidation may close
+  pub liquidation_limits: LiquidationLimits,
+
+  // zeroed, new fields are carved out of it and announced by a version bump
+  pub reserved: [u8; MARKET_RESERVED_BYTES],
+}
+
+// Disabled while pre_lltv is zero. Between pre_lltv and the liquidation ltv the close
+// and incentive factors rise linearly from their _1 to their _2 value.
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct PreLiquidationConfig {
+  // scaled by LTV_PRECISION
+  pub pre_lltv: u64,
+  // share of the borrow shares one pre-liquidation may repay, scaled by WAD
+  pub pre_lcf_1: u64,
+  pub pre_lcf_2: u64,
+  // incentive factors, scaled by WAD
+  pub pre_lif_1: u64,
+  pub pre_lif_2: u64,
+}
+
+// Zeroed fields are not enforced. Past the full close health, or when a capped
+// liquidation would leave less than min_remaining_debt, the whole position may be closed.
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct LiquidationLimits {
+  // share of the borrow shares one liquidation may repay, scaled by WAD
+  pub close_factor: u64,
+  // liquidation health at or below which the close factor is lifted, scaled by WAD
+  pub full_close_health: u64,
+  // least debt in quote tokens a partial liquidation may leave behind
+  pub min_remaining_debt: u64,
+}
+
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct MarketPause {
+  pub deposit: bool,
+  pub borrow: bool,
+  pub withdraw: bool,
+  pub withdraw_collateral: bool,
+  pub liquidate: bool,
 }
 
 impl Market {
@@ -40,6 +111,75 @@ impl Market {
         .w_mul_down(Decimal::from_raw_u64(self.total_borrow_shares))?
         .to_u64()
   }
+
+  /// Ltv bytes of the market address. Markets created before the seed ltv was
+  /// stored derive their address from the max borrow ltv.
+  pub fn seed_ltv_bytes(&self) -> [u8; 8] {
+    if self.seed_ltv == 0 {
+      self.max_borrow_ltv.to_le_bytes()
+    } else {
+      self.seed_ltv.to_le_bytes()
+    }
+  }
+
+  /// The ltv past which positions can be liquidated. Markets without a
+  /// liquidation ltv liquidate at the max borrow ltv.
+  pub fn effective_liquidation_ltv(&self) -> u64 {
+    if self.liquidation_ltv == 0 {
+      self.max_borrow_ltv
+    } else {
+      self.liquidation_ltv
+    }
+  }
+
+  /// Whether bad debt has written the deposit index down to zero.
+  pub fn is_wiped_out(&self) -> bool {
+    self.deposit_index == 0
+  }
+
+  /// Removes `bad_debt_shares` from the borrow side and writes down the deposit
+  /// index by the same amount of assets, so lenders absorb the loss pro rata.
+  /// A loss wiping out every deposit pauses deposits and borrows for good.
+  /// Returns the amount of bad debt realized.
+  pub fn realize_bad_debt(&mut self, bad_debt_shares: u64) -> Result<u64> {
+    let total_borrows = self.total_borrows()?;
+    let total_deposits = self.total_deposits()?;
+
+    let bad_debt = min_u64(
+      total_borrows,
+      to_assets_up(bad_debt_shares, total_borrows, self.total_borrow_shares)?,
+    );
+
+    self.total_borrow_shares = self
+      .total_borrow_shares
+      .checked_sub(bad_debt_shares)
+      .ok_or(MarketError::MathUnderflow)?;
+
+    if total_deposits > 0 {
+      let remaining_deposits = total_deposits.saturating_sub(bad_debt);
+
+      self.deposit_index = Decimal::from_raw_u128(self.deposit_index)
+        .mul_div_down(
+          Decimal::from_raw_u64(remaining_deposits),
+          Decimal::from_raw_u64(total_deposits),
+        )?
+        .to_u128()?;
+
+      // lenders lost everything, an index of zero can never grow back so new
+      // deposits and borrows are shut off for good
+      if self.is_wiped_out() {
+        self.paused.deposit = true;
+        self.paused.borrow = true;
+      }
+    }
+
+    self.bad_debt = self
+      .bad_debt
+      .checked_add(bad_debt)
+      .ok_or(MarketError::MathOverflow)?;
+
+    Ok(bad_debt)
+  }
 }
 
 #[account]
@@ -55,6 +195,46 @@ pub struct BorrowerShares {
   pub collateral_amount: u64,
 }
 
+// Created by a borrower to allow pre-liquidations of their position in a market
+#[account]
+pub struct PreLiquidationAuthorization {
+  pub bump: u8,
+  pub enabled: bool,
+}
+
+// Tracks since when a position has been liquidatable, read by auction liquidations
+#[account]
+pub struct LiquidationState {
+  pub bump: u8,
+  // unix timestamp, zero while the position is healthy
+  pub insolvent_since: u64,
+  // the position when it was recorded, any change by the borrower voids the record
+  pub borrow_shares: u64,
+  pub collateral_amount: u64,
+}
+
+impl LiquidationState {
+  // A borrower action may have cured the position without anyone observing it,
+  // so the record only holds while the position is untouched since
+  pub fn is_current(&self, position: &BorrowerShares) -> bool {
+    self.insolvent_since != 0
+      && self.borrow_shares == position.borrow_shares
+      && self.collateral_amount == position.collateral_amount
+  }
+
+  pub fn record(&mut self, insolvent_since: u64, position: &BorrowerShares) {
+    self.insolvent_since = insolvent_since;
+    self.borrow_shares = position.borrow_shares;
+    self.collateral_amount = position.collateral_amount;
+  }
+
+  pub fn clear(&mut self) {
+    self.insolvent_since = 0;
+    self.borrow_shares = 0;
+    self.collateral_amount = 0;
+  }
+}
+
 #[account]
 pub struct PositionDelegate {
   pub bump: u8,
@@ -68,7 +248,7 @@ macro_rules! generate_market_seeds {
       MARKET_SEED_PREFIX,
       $market.quote_mint.as_ref(),
       $market.collateral_mint.as_ref(),
-      &$market.ltv_factor.to_le_bytes(),
+      &$market.seed_ltv_bytes(),
       &$market.oracle.id.to_bytes(),
       &[$market.bump],
     ]
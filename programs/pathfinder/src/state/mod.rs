@@ -1,9 +1,13 @@
 pub mod config;
 pub mod constants;
+pub mod irm;
+pub mod legacy;
 pub mod market;
 pub mod oracle;
 
 pub use config::*;
 pub use constants::*;
+pub use irm::*;
+pub use legacy::*;
 pub use market::*;
 pub use oracle::*;
\ No newline at end of file
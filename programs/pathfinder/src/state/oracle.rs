@@ -1,12 +1,56 @@
 use anchor_lang::prelude::*;
 
 #[derive(
-  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, Ord, PartialOrd,
+  AnchorSerialize,
+  AnchorDeserialize,
+  Clone,
+  Copy,
+  Eq,
+  PartialEq,
+  Debug,
+  Default,
+  Ord,
+  PartialOrd,
+  InitSpace,
 )]
 pub enum OracleSource {
   #[default]
   PythPull,
   SwitchboardPull,
+  // base / quote, each read from its own feed account
+  Composite {
+    base: FeedSource,
+    quote: FeedSource,
+  },
+}
+
+// Single feed that can be part of a composite price
+#[derive(
+  AnchorSerialize,
+  AnchorDeserialize,
+  Clone,
+  Copy,
+  Eq,
+  PartialEq,
+  Debug,
+  Default,
+  Ord,
+  PartialOrd,
+  InitSpace,
+)]
+pub enum FeedSource {
+  #[default]
+  PythPull,
+  SwitchboardPull,
+}
+
+impl From<FeedSource> for OracleSource {
+  fn from(feed: FeedSource) -> Self {
+    match feed {
+      FeedSource::PythPull => OracleSource::PythPull,
+      FeedSource::SwitchboardPull => OracleSource::SwitchboardPull,
+    }
+  }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
@@ -15,9 +59,54 @@ pub struct Price {
   pub scale: u64,
 }
 
+// Feed reading that passed validation, price is always positive
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OraclePrice {
+  pub price: u64,
+  pub conf: u64,
+  pub scale: u64,
+}
+
+// Mirrors the pyth receiver verification level
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub enum PythVerificationLevel {
+  Partial {
+    num_signatures: u8,
+  },
+  #[default]
+  Full,
+}
+
+// Risk settings checked on every price read
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct OracleConfig {
+  pub max_age: u64,         // seconds
+  pub max_conf_bps: u64,    // confidence / price
+  pub price_floor_bps: u64, // lower bound never goes under this share of the price
+  pub pyth_verification_level: PythVerificationLevel,
+  pub min_sb_responses: u32,
+}
+
+// Secondary feed for the same pair, read when the primary fails
+#[derive(
+  AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug, Default, InitSpace,
+)]
+pub struct FallbackOracle {
+  pub id: Pubkey,
+  pub source: FeedSource,
+  pub max_deviation_bps: u64, // from the primary when both can be read
+}
+
 // Base struct that contains common data
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
 pub struct Oracle {
-  pub id: Pubkey,
+  pub id: Pubkey, // base feed for composite
   pub source: OracleSource,
-}
\ No newline at end of file
+  pub config: OracleConfig,
+  pub quote_id: Pubkey, // only set for composite
+  pub fallback: Option<FallbackOracle>,
+}
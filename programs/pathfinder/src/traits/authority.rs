@@ -4,12 +4,19 @@ use anchor_lang::prelude::*;
 
 pub trait AuthorityProtection<'info> {
   fn is_authority(&self, user: &Signer, config: &Account<'info, Config>) -> Result<()> {
-    if config.authority != Pubkey::default() {
-      require!(
-        user.key() == config.authority,
-        MarketError::InvalidAuthority
-      );
-    }
+    require!(
+      config.authority != Pubkey::default() && user.key() == config.authority,
+      MarketError::InvalidAuthority
+    );
+    Ok(())
+  }
+
+  // the authority can always act as guardian
+  fn is_guardian(&self, user: &Signer, config: &Account<'info, Config>) -> Result<()> {
+    let is_guardian = config.guardian != Pubkey::default() && user.key() == config.guardian;
+    let is_authority = config.authority != Pubkey::default() && user.key() == config.authority;
+
+    require!(is_guardian || is_authority, MarketError::InvalidGuardian);
     Ok(())
   }
 }
\ No newline at end of file
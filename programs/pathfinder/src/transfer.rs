@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+  self,
+  extension::{
+    transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+  },
+};
+use anchor_spl::token_interface::{
+  transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::MarketError;
+
+// Mint extensions that let a third party move, freeze or block the market vaults
+pub const UNSUPPORTED_MINT_EXTENSIONS: [ExtensionType; 4] = [
+  ExtensionType::PermanentDelegate,
+  ExtensionType::NonTransferable,
+  ExtensionType::TransferHook,
+  ExtensionType::DefaultAccountState,
+];
+
+/// Transfers `amount` into a market vault and returns the amount the vault
+/// actually received, which is lower than `amount` for mints with a transfer fee.
+pub fn transfer_to_vault<'info>(
+  token_program: &Interface<'info, TokenInterface>,
+  mint: &InterfaceAccount<'info, Mint>,
+  from: &InterfaceAccount<'info, TokenAccount>,
+  vault: &mut InterfaceAccount<'info, TokenAccount>,
+  authority: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<u64> {
+  let balance_before = vault.amount;
+
+  transfer_checked(
+    CpiContext::new(
+      token_program.to_account_info(),
+      TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: vault.to_account_info(),
+        authority: authority.clone(),
+      },
+    ),
+    amount,
+    mint.decimals,
+  )?;
+
+  vault.reload()?;
+
+  vault
+    .amount
+    .checked_sub(balance_before)
+    .ok_or(error!(MarketError::MathUnderflow))
+}
+
+/// Transfers `amount` out of a market vault, signed by the market.
+pub fn transfer_from_vault<'info>(
+  token_program: &Interface<'info, TokenInterface>,
+  mint: &InterfaceAccount<'info, Mint>,
+  vault: &InterfaceAccount<'info, TokenAccount>,
+  to: &InterfaceAccount<'info, TokenAccount>,
+  market: &AccountInfo<'info>,
+  amount: u64,
+  signer: &[&[&[u8]]],
+) -> Result<()> {
+  transfer_checked(
+    CpiContext::new_with_signer(
+      token_program.to_account_info(),
+      TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: market.clone(),
+      },
+      signer,
+    ),
+    amount,
+    mint.decimals,
+  )
+}
+
+/// Returns the amount to send so that `net_amount` arrives after transfer fees.
+pub fn amount_with_transfer_fee(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+  let mint_info = mint.to_account_info();
+  if *mint_info.owner != spl_token_2022::ID {
+    return Ok(net_amount);
+  }
+
+  let mint_data = mint_info.try_borrow_data()?;
+  let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+  match mint_state.get_extension::<TransferFeeConfig>() {
+    Ok(fee_config) => {
+      let fee = fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+        .ok_or(MarketError::MathOverflow)?;
+
+      Ok(
+        net_amount
+          .checked_add(fee)
+          .ok_or(MarketError::MathOverflow)?,
+      )
+    }
+    Err(_) => Ok(net_amount),
+  }
+}
+
+/// Rejects Token-2022 mints carrying any of `UNSUPPORTED_MINT_EXTENSIONS`.
+pub fn validate_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+  let mint_info = mint.to_account_info();
+  if *mint_info.owner != spl_token_2022::ID {
+    return Ok(());
+  }
+
+  let mint_data = mint_info.try_borrow_data()?;
+  let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+  for extension in mint_state.get_extension_types()? {
+    require!(
+      !UNSUPPORTED_MINT_EXTENSIONS.contains(&extension),
+      MarketError::UnsupportedMintExtension
+    );
+  }
+
+  Ok(())
+}
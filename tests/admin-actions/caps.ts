@@ -0,0 +1,155 @@
+import { TestUtils } from "../utils";
+import { MarketFixture, UserFixture } from "../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+describe("Market Caps", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let larry: UserFixture;
+  let bob: UserFixture;
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(1_000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(1_000 * 1e9),
+      new anchor.BN(1_000 * 1e9)
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      supplyCap: new anchor.BN(500 * 1e9),
+      borrowCap: new anchor.BN(100 * 1e9),
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(10 * 1e9),
+      owner: bob,
+    });
+  });
+
+  it("enforces the supply cap on deposits", async () => {
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(500 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.deposit({
+          user: larry,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: larry,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Supply cap exceeded");
+        return true;
+      }
+    );
+  });
+
+  it("enforces the borrow cap on borrows", async () => {
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(500 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(100 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Borrow cap exceeded");
+        return true;
+      }
+    );
+  });
+
+  it("lets the authority raise or remove caps", async () => {
+    await assert.rejects(
+      async () => {
+        await market.updateMarketCaps({
+          user: larry,
+          supplyCap: new anchor.BN(0),
+          borrowCap: new anchor.BN(0),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    await market.updateMarketCaps({
+      user: futarchy,
+      supplyCap: new anchor.BN(0),
+      borrowCap: new anchor.BN(200 * 1e9),
+    });
+
+    const marketData = await market.marketAcc.get_data();
+    assert.strictEqual(marketData.supplyCap.toString(), "0");
+    assert.strictEqual(marketData.borrowCap.toString(), (200 * 1e9).toString());
+
+    // zero cap means uncapped
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(150 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+  });
+});
@@ -1,5 +1,5 @@
 import { TestUtils } from "../utils";
-import { MarketFixture, UserFixture } from "../fixtures";
+import { DEFAULT_CURVE_LIMITS, MarketFixture, UserFixture } from "../fixtures";
 import * as anchor from "@coral-xyz/anchor";
 import assert from "assert";
 
@@ -39,7 +39,7 @@ describe("Config Operations", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(10 * 1e9),
       expo: -9,
@@ -58,7 +58,7 @@ describe("Config Operations", () => {
 
     await assert.rejects(
       async () => {
-        await market.updateAuthority({
+        await market.proposeAuthority({
           user: larry,
           new_authority: lilly,
         });
@@ -70,16 +70,120 @@ describe("Config Operations", () => {
       }
     );
 
-    await market.updateAuthority({
+    await market.proposeAuthority({
       user: futarchy,
       new_authority: lilly,
     });
 
+    // the authority only changes once the proposed authority accepts
+    const pendingConfigData = await market.get_config().get_data();
+    assert.equal(pendingConfigData.authority.toBase58(), futarchy.key.publicKey.toBase58());
+    assert.equal(pendingConfigData.pendingAuthority.toBase58(), lilly.key.publicKey.toBase58());
+
+    await assert.rejects(
+      async () => {
+        await market.acceptAuthority({ user: larry });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    await market.acceptAuthority({ user: lilly });
+
     const postConfigData = await market.get_config().get_data();
     assert.equal(postConfigData.authority.toBase58(), lilly.key.publicKey.toBase58());
+    assert.equal(postConfigData.pendingAuthority.toBase58(), anchor.web3.PublicKey.default.toBase58());
 
   });
 
+  it("cancels a proposed authority", async () => {
+    await market.proposeAuthority({
+      user: futarchy,
+      new_authority: lilly,
+    });
+
+    await market.cancelAuthority({ user: futarchy });
+
+    await assert.rejects(
+      async () => {
+        await market.acceptAuthority({ user: lilly });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    const postConfigData = await market.get_config().get_data();
+    assert.equal(postConfigData.authority.toBase58(), futarchy.key.publicKey.toBase58());
+  });
+
+  it("initializes the config only once", async () => {
+    await assert.rejects(
+      async () => {
+        await market.initializeConfig({
+          user: larry,
+          authority: larry,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        // Account already exists error
+        return true;
+      },
+      "Expected config initialization to fail when config already exists"
+    );
+
+    const postConfigData = await market.get_config().get_data();
+    assert.equal(postConfigData.authority.toBase58(), futarchy.key.publicKey.toBase58());
+  });
+
+  it("only lets the program upgrade authority initialize the config", async () => {
+    const freshTest = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    const deployer = await freshTest.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+    const frontRunner = await freshTest.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    const freshMarket = await freshTest.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: deployer,
+      authority: deployer,
+    });
+
+    await assert.rejects(
+      async () => {
+        await freshMarket.initializeConfig({
+          user: frontRunner,
+          upgradeAuthority: deployer,
+          authority: frontRunner,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    await freshMarket.initializeConfig({ user: deployer });
+
+    const configData = await freshMarket.get_config().get_data();
+    assert.equal(configData.authority.toBase58(), deployer.key.publicKey.toBase58());
+  });
+
   it("sets and restricts update fee based on authority", async () => {
     const preConfigData = await market.get_config().get_data();
     assert.equal(preConfigData.feeFactor.toString(), "0");
@@ -135,6 +239,53 @@ describe("Config Operations", () => {
     assert.equal(postConfigData.feeRecipient.toBase58(), lilly.key.publicKey.toBase58());
   });
 
+  it("sets and restricts curve limits based on authority", async () => {
+    const newCurveLimits = {
+      ...DEFAULT_CURVE_LIMITS,
+      minTargetUtilization: new anchor.BN("700000000000000000"),
+    };
+
+    await assert.rejects(
+      async () => {
+        await market.updateCurveLimits({
+          user: larry,
+          curveLimits: newCurveLimits,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await market.updateCurveLimits({
+          user: futarchy,
+          curveLimits: {
+            ...newCurveLimits,
+            maxTargetUtilization: new anchor.BN("1000000000000000000"),
+          },
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid interest rate model");
+        return true;
+      }
+    );
+
+    await market.updateCurveLimits({
+      user: futarchy,
+      curveLimits: newCurveLimits,
+    });
+
+    const postConfigData = await market.get_config().get_data();
+    assert.equal(
+      postConfigData.curveLimits.minTargetUtilization.toString(),
+      "700000000000000000"
+    );
+  });
+
 
   it("correctly for a year with protocol fee", async () => {
     // Setup initial state: deposit, collateralize, and borrow
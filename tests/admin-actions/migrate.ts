@@ -0,0 +1,217 @@
+import { TestUtils } from "../utils";
+import { DEFAULT_CURVE_LIMITS, DEFAULT_ORACLE_CONFIG, MarketFixture, UserFixture } from "../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+describe("Migrate", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let larry: UserFixture;
+  let bob: UserFixture;
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    // pre-upgrade fixture, with balances that must survive the migration
+    await market.setLegacyConfig({
+      authority: larry.key.publicKey,
+      feeFactor: new anchor.BN("50000000000000000"),
+      feeRecipient: futarchy.key.publicKey,
+    });
+
+    await market.setLegacyMarket({
+      depositIndex: new anchor.BN("1010000000000000000"),
+      totalShares: new anchor.BN(1_000 * 1e9),
+      quoteMint: market.quoteMint,
+      quoteMintDecimals: 9,
+      borrowIndex: new anchor.BN("1020000000000000000"),
+      totalBorrowShares: new anchor.BN(400 * 1e9),
+      totalCollateral: new anchor.BN(10 * 1e9),
+      collateralMint: market.collateral.collateralMint,
+      collateralMintDecimals: 9,
+      ltvFactor: market.collateral._maxBorrowLtv,
+      oracleId: market.collateral.getOracleId(),
+      oracleSource: 0,
+      rateAtTarget: new anchor.BN(1_268_391_679),
+      lastAccrualTimestamp: new anchor.BN(1_700_000_000),
+      feeShares: new anchor.BN(3 * 1e9),
+    });
+  });
+
+  it("upgrades a legacy config and market", async () => {
+    await market.migrateConfig({ user: larry });
+    await market.migrateMarket({ user: larry, liquidationLtv: new anchor.BN(0.85 * 1e9) });
+
+    const config = await market.get_config().get_data();
+    assert.equal(config.version, 1);
+    assert.equal(config.authority.toBase58(), larry.key.publicKey.toBase58());
+    assert.equal(config.feeFactor.toString(), "50000000000000000");
+    assert.equal(config.feeRecipient.toBase58(), futarchy.key.publicKey.toBase58());
+    assert.equal(config.guardian.toBase58(), anchor.web3.PublicKey.default.toBase58());
+    assert.equal(
+      config.curveLimits.maxCurveSteepness.toString(),
+      DEFAULT_CURVE_LIMITS.maxCurveSteepness.toString()
+    );
+
+    const marketData = await market.marketAcc.get_data();
+    assert.equal(marketData.version, 1);
+    assert.equal(marketData.depositIndex.toString(), "1010000000000000000");
+    assert.equal(marketData.totalShares.toString(), (1_000 * 1e9).toString());
+    assert.equal(marketData.borrowIndex.toString(), "1020000000000000000");
+    assert.equal(marketData.totalBorrowShares.toString(), (400 * 1e9).toString());
+    assert.equal(marketData.totalCollateral.toString(), (10 * 1e9).toString());
+    assert.equal(marketData.maxBorrowLtv.toString(), market.collateral._maxBorrowLtv.toString());
+    assert.equal(marketData.seedLtv.toString(), market.collateral._maxBorrowLtv.toString());
+    assert.equal(marketData.liquidationLtv.toString(), (0.85 * 1e9).toString());
+    assert.equal(marketData.oracle.id.toBase58(), market.collateral.getOracleId().toBase58());
+    assert.deepEqual(marketData.oracle.source, { pythPull: {} });
+    assert.equal(marketData.oracle.config.maxAge.toString(), DEFAULT_ORACLE_CONFIG.maxAge.toString());
+    assert.equal(marketData.rateAtTarget.toString(), "1268391679");
+    assert.equal(marketData.feeShares.toString(), (3 * 1e9).toString());
+    assert.equal(marketData.supplyCap.toNumber(), 0);
+    assert.equal(marketData.borrowCap.toNumber(), 0);
+    assert.equal(marketData.paused.deposit, false);
+  });
+
+  it("converts the ltv factor of a market with a 6 decimal quote", async () => {
+    const sixDecimals = await TestUtils.create({
+      quoteDecimals: 6,
+      collateralDecimals: 8,
+    });
+
+    const admin = await sixDecimals.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    // the legacy factor scaled debt by the quote decimals, 0.8 * 1e6 is 80%
+    // and is what the market address was derived from
+    const legacyMarket = await sixDecimals.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e6),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: admin,
+      authority: admin,
+    });
+
+    await legacyMarket.setLegacyConfig({
+      authority: admin.key.publicKey,
+      feeFactor: new anchor.BN(0),
+      feeRecipient: admin.key.publicKey,
+    });
+
+    await legacyMarket.setLegacyMarket({
+      depositIndex: new anchor.BN("1000000000000000000"),
+      totalShares: new anchor.BN(1_000 * 1e6),
+      quoteMint: legacyMarket.quoteMint,
+      quoteMintDecimals: 6,
+      borrowIndex: new anchor.BN("1000000000000000000"),
+      totalBorrowShares: new anchor.BN(400 * 1e6),
+      totalCollateral: new anchor.BN(10 * 1e8),
+      collateralMint: legacyMarket.collateral.collateralMint,
+      collateralMintDecimals: 8,
+      ltvFactor: new anchor.BN(0.8 * 1e6),
+      oracleId: legacyMarket.collateral.getOracleId(),
+      oracleSource: 0,
+      rateAtTarget: new anchor.BN(1_268_391_679),
+      lastAccrualTimestamp: new anchor.BN(await sixDecimals.getTime()),
+      feeShares: new anchor.BN(0),
+    });
+
+    await legacyMarket.migrateConfig({ user: admin });
+    await legacyMarket.migrateMarket({ user: admin, liquidationLtv: new anchor.BN(0.85 * 1e9) });
+
+    const marketData = await legacyMarket.marketAcc.get_data();
+    assert.equal(marketData.maxBorrowLtv.toString(), (0.8 * 1e9).toString());
+    assert.equal(marketData.seedLtv.toString(), (0.8 * 1e6).toString());
+
+    // still found at the address derived from the legacy factor
+    await legacyMarket.accrueInterest();
+  });
+
+  it("fails to migrate a market twice", async () => {
+    await market.migrateConfig({ user: larry });
+    await market.migrateMarket({ user: larry, liquidationLtv: new anchor.BN(0.85 * 1e9) });
+
+    await assert.rejects(
+      async () => {
+        await market.migrateMarket({ user: larry, liquidationLtv: new anchor.BN(0.85 * 1e9) });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Account is already on the latest version");
+        return true;
+      }
+    );
+  });
+
+  it("fails to migrate a market with a liquidation ltv below the legacy ltv", async () => {
+    await market.migrateConfig({ user: larry });
+
+    await assert.rejects(
+      async () => {
+        await market.migrateMarket({ user: larry, liquidationLtv: new anchor.BN(0.8 * 1e9) });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Max borrow ltv must be below the liquidation ltv");
+        return true;
+      }
+    );
+  });
+
+  it("fails to migrate a config from a non-authority", async () => {
+    await assert.rejects(
+      async () => {
+        await market.migrateConfig({ user: bob });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+  });
+
+  it("fails to migrate a market from a non-authority", async () => {
+    await market.migrateConfig({ user: larry });
+
+    await assert.rejects(
+      async () => {
+        await market.migrateMarket({ user: bob, liquidationLtv: new anchor.BN(0.85 * 1e9) });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+  });
+});
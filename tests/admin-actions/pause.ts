@@ -0,0 +1,220 @@
+import { TestUtils } from "../utils";
+import { MarketFixture, UserFixture } from "../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+describe("Market Pause", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let larry: UserFixture;
+  let bob: UserFixture;
+  let guardian: UserFixture;
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(1_000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(1_000 * 1e9),
+      new anchor.BN(1_000 * 1e9)
+    );
+
+    guardian = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.updateGuardian({
+      user: futarchy,
+      newGuardian: guardian,
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(500 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(10 * 1e9),
+      owner: bob,
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(100 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+  });
+
+  it("stops borrows while repay and collateral deposits still work", async () => {
+    await market.setMarketPause({
+      user: guardian,
+      borrow: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+
+    await market.repay({
+      user: bob,
+      amount: new anchor.BN(50 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1 * 1e9),
+      owner: bob,
+    });
+
+    // lenders are unaffected by a borrow pause
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+  });
+
+  it("stops deposits and withdrawals separately", async () => {
+    await market.setMarketPause({
+      user: futarchy,
+      deposit: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.deposit({
+          user: larry,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: larry,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+
+    await market.withdraw({
+      user: larry,
+      amount: new anchor.BN(1 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+      recipient: larry,
+    });
+
+    await market.setMarketPause({
+      user: guardian,
+      withdraw: true,
+      withdrawCollateral: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.withdraw({
+          user: larry,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: larry,
+          recipient: larry,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await market.withdrawCollateral({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+  });
+
+  it("only lets the guardian or authority pause", async () => {
+    await assert.rejects(
+      async () => {
+        await market.setMarketPause({
+          user: larry,
+          borrow: true,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid guardian");
+        return true;
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await market.updateGuardian({
+          user: guardian,
+          newGuardian: larry,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid authority");
+        return true;
+      }
+    );
+
+    const marketData = await market.marketAcc.get_data();
+    assert.equal(marketData.paused.borrow, false);
+  });
+});
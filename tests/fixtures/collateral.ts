@@ -28,9 +28,16 @@ export const ORACLE_CONFIG = {
 
 export type SupportedCollateral = keyof typeof ORACLE_CONFIG;
 
+// quote leg of composite markets, both legs read from pyth
+export const COMPOSITE_QUOTE_PYTH_ID = "0xeaa020c61cc479712813461ce153894a96a6c00b21ed0cfc2798d1f9a9e9c94a"; // USDC-USD
+
+// test-only feed for the secondary oracle, read from pyth
+export const FALLBACK_PYTH_ID = "0x" + "fa".repeat(32);
+
 export enum OracleSource {
   PythPull,
-  SwitchboardPull
+  SwitchboardPull,
+  Composite
 }
 
 export class CollateralFixture {
@@ -41,7 +48,10 @@ export class CollateralFixture {
   public symbol: SupportedCollateral;
   public oracleId: PublicKey;
   public oracleAcc: anchor.web3.Keypair;
-  public ltvFactor: anchor.BN;
+  public quoteOracleAcc: anchor.web3.Keypair;
+  public fallbackOracleAcc: anchor.web3.Keypair;
+  public hasFallback: boolean = false;
+  public maxBorrowLtv: anchor.BN;
   public oracleSource: OracleSource;
 
   constructor(
@@ -49,7 +59,7 @@ export class CollateralFixture {
     public _program: Program<Markets>,
     public _provider: BankrunProvider,
     public _collateralMint: PublicKey,
-    public _ltvFactor: anchor.BN,
+    public _maxBorrowLtv: anchor.BN,
     public _oracleSource: OracleSource = OracleSource.PythPull,
   ) {
     this.symbol = _symbol;
@@ -58,17 +68,19 @@ export class CollateralFixture {
     this.collateralMint = _collateralMint;
     this.oracleProgram = new Program<MockPythPull>(PythIDL, this.provider)
     this.oracleAcc = new anchor.web3.Keypair();
-    this.ltvFactor = _ltvFactor;
+    this.quoteOracleAcc = new anchor.web3.Keypair();
+    this.fallbackOracleAcc = new anchor.web3.Keypair();
+    this.maxBorrowLtv = _maxBorrowLtv;
     this.oracleSource = _oracleSource;
   }
 
   getOracleId(): PublicKey {
     const config = ORACLE_CONFIG[this.symbol];
-    const idString = this.oracleSource === OracleSource.PythPull 
+    const idString = this.oracleSource !== OracleSource.SwitchboardPull
       ? config.pyth_id 
       : config.sb_id;
       
-    if (this.oracleSource === OracleSource.PythPull) {
+    if (this.oracleSource !== OracleSource.SwitchboardPull) {
       // Convert Pyth hex string to PublicKey
       const bytes = Buffer.from(idString.replace('0x', ''), 'hex');
       return new PublicKey(bytes);
@@ -78,12 +90,31 @@ export class CollateralFixture {
     }
   }
 
+  getQuoteOracleId(): PublicKey {
+    if (this.oracleSource !== OracleSource.Composite) {
+      return PublicKey.default;
+    }
+
+    return new PublicKey(Buffer.from(COMPOSITE_QUOTE_PYTH_ID.replace('0x', ''), 'hex'));
+  }
+
   getOracleSource(): OracleSource {
     return this.oracleSource;
   }
 
+  getOracleSourceArg(): any {
+    switch (this.oracleSource) {
+      case OracleSource.PythPull:
+        return { pythPull: {} };
+      case OracleSource.SwitchboardPull:
+        return { switchboardPull: {} };
+      case OracleSource.Composite:
+        return { composite: { base: { pythPull: {} }, quote: { pythPull: {} } } };
+    }
+  }
+
   getOracleAccount(): PublicKey {
-    if (this.oracleSource === OracleSource.PythPull) {
+    if (this.oracleSource !== OracleSource.SwitchboardPull) {
       // account is the same accross all pyth feeds
       return this.oracleAcc.publicKey;
     } else {
@@ -97,7 +128,27 @@ export class CollateralFixture {
     return ORACLE_CONFIG[this.symbol].decimals;
   }
 
-  async initPrice({
+  getQuoteOracleAccount(): PublicKey | null {
+    if (this.oracleSource !== OracleSource.Composite) {
+      return null;
+    }
+
+    return this.quoteOracleAcc.publicKey;
+  }
+
+  getFallbackOracleId(): PublicKey {
+    return new PublicKey(Buffer.from(FALLBACK_PYTH_ID.replace('0x', ''), 'hex'));
+  }
+
+  getFallbackOracleAccount(): PublicKey | null {
+    if (!this.hasFallback) {
+      return null;
+    }
+
+    return this.fallbackOracleAcc.publicKey;
+  }
+
+  async initFallbackPrice({
     price,
     conf,
     expo
@@ -106,7 +157,65 @@ export class CollateralFixture {
     conf: anchor.BN,
     expo: number
   }) {
-    if (this.oracleSource === OracleSource.PythPull) {
+    await this.oracleProgram.methods.initialize(
+      this.getFallbackOracleId(),
+      price,
+      conf,
+      expo,
+    ).accounts({
+      payer: this.provider.wallet.publicKey,
+      price: this.fallbackOracleAcc.publicKey,
+    })
+    .signers([this.fallbackOracleAcc])
+    .rpc();
+
+    this.hasFallback = true;
+  }
+
+  async setFallbackPrice({
+    price,
+    conf
+  }: {
+    price: anchor.BN,
+    conf: anchor.BN
+  }) {
+    await this.oracleProgram.methods.setPrice(
+      price,
+      conf,
+    ).accounts({
+      price: this.fallbackOracleAcc.publicKey,
+    })
+    .rpc();
+  }
+
+  async initPrice({
+    price,
+    conf,
+    expo,
+    quotePrice = new anchor.BN(1e9),
+    quoteConf = new anchor.BN(0),
+  }: {
+    price: anchor.BN,
+    conf: anchor.BN,
+    expo: number,
+    quotePrice?: anchor.BN,
+    quoteConf?: anchor.BN,
+  }) {
+    if (this.oracleSource === OracleSource.Composite) {
+      await this.oracleProgram.methods.initialize(
+        this.getQuoteOracleId(),
+        quotePrice,
+        quoteConf,
+        -9,
+      ).accounts({
+        payer: this.provider.wallet.publicKey,
+        price: this.quoteOracleAcc.publicKey,
+      })
+      .signers([this.quoteOracleAcc])
+      .rpc();
+    }
+
+    if (this.oracleSource !== OracleSource.SwitchboardPull) {
       await this.oracleProgram.methods.initialize(
         this.getOracleId(),
         price,
@@ -141,7 +250,7 @@ export class CollateralFixture {
     price: anchor.BN,
     conf: anchor.BN
   }) {
-    if (this.oracleSource === OracleSource.PythPull) {
+    if (this.oracleSource !== OracleSource.SwitchboardPull) {
       await this.oracleProgram.methods.setPrice(
         price,
         conf,
@@ -154,4 +263,27 @@ export class CollateralFixture {
     // can't modify switchboard price oracle
 
   }
+
+  // rewrites the slot each leading submission of the switchboard snapshot
+  // landed at, the snapshot predates land slots and leaves them all at 0
+  async setSwitchboardLandedAt(landedAt: number[]) {
+    const account = await this.provider.context.banksClient.getAccount(
+      this.getOracleAccount()
+    );
+    const data = Buffer.from(account.data);
+
+    // 8 byte discriminator, then 64 byte submissions with landed_at at offset 40
+    landedAt.forEach((slot, i) => {
+      data.writeBigUInt64LE(BigInt(slot), 8 + i * 64 + 40);
+    });
+
+    create_custom_account(
+      this.provider.context,
+      this.getOracleAccount(),
+      account.owner,
+      Number(account.lamports),
+      data,
+      Number(account.rentEpoch)
+    );
+  }
 }
@@ -3,4 +3,4 @@ export * from "./collateral";
 export * from "./user";
 export * from "./market";
 export * from "./account";
-export * from "./manager";
\ No newline at end of file
+export * from "./manager";export * from "./legacy";
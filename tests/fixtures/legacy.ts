@@ -0,0 +1,72 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { createHash } from "crypto";
+
+// Account layouts written before versioning (version 0). The IDL only describes
+// the current layouts, so these are encoded by hand.
+
+export type LegacyMarket = {
+  bump: number;
+  depositIndex: anchor.BN;
+  totalShares: anchor.BN;
+  quoteMint: PublicKey;
+  quoteMintDecimals: number;
+  borrowIndex: anchor.BN;
+  totalBorrowShares: anchor.BN;
+  totalCollateral: anchor.BN;
+  collateralMint: PublicKey;
+  collateralMintDecimals: number;
+  ltvFactor: anchor.BN;
+  oracleId: PublicKey;
+  oracleSource: number; // 0 pyth, 1 switchboard
+  rateAtTarget: anchor.BN;
+  lastAccrualTimestamp: anchor.BN;
+  feeShares: anchor.BN;
+};
+
+export type LegacyConfig = {
+  bump: number;
+  authority: PublicKey;
+  feeFactor: anchor.BN;
+  feeRecipient: PublicKey;
+};
+
+function discriminator(name: string): Buffer {
+  return createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
+}
+
+const u8 = (value: number) => Buffer.from([value]);
+const u64 = (value: anchor.BN) => value.toArrayLike(Buffer, "le", 8);
+const u128 = (value: anchor.BN) => value.toArrayLike(Buffer, "le", 16);
+
+export function encodeLegacyMarket(market: LegacyMarket): Buffer {
+  return Buffer.concat([
+    discriminator("Market"),
+    u8(market.bump),
+    u128(market.depositIndex),
+    u64(market.totalShares),
+    market.quoteMint.toBuffer(),
+    u8(market.quoteMintDecimals),
+    u128(market.borrowIndex),
+    u64(market.totalBorrowShares),
+    u64(market.totalCollateral),
+    market.collateralMint.toBuffer(),
+    u8(market.collateralMintDecimals),
+    u64(market.ltvFactor),
+    market.oracleId.toBuffer(),
+    u8(market.oracleSource),
+    u128(market.rateAtTarget),
+    u64(market.lastAccrualTimestamp),
+    u64(market.feeShares),
+  ]);
+}
+
+export function encodeLegacyConfig(config: LegacyConfig): Buffer {
+  return Buffer.concat([
+    discriminator("Config"),
+    u8(config.bump),
+    config.authority.toBuffer(),
+    u64(config.feeFactor),
+    config.feeRecipient.toBuffer(),
+  ]);
+}
@@ -3,11 +3,43 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Markets } from "../../target/types/markets";
 import { BankrunProvider } from "anchor-bankrun";
-import { CollateralFixture, SupportedCollateral, UserFixture, AccountFixture, marketAccountFixture, splAccountFixture, ControllerFixture, OracleSource } from "./index";
-import { deriveMarketAddress } from "../utils";
+import { CollateralFixture, SupportedCollateral, UserFixture, AccountFixture, marketAccountFixture, splAccountFixture, ControllerFixture, LegacyConfig, LegacyMarket, encodeLegacyConfig, encodeLegacyMarket } from "./index";
+import { create_custom_account, deriveMarketAddress } from "../utils";
 import { assert } from "chai";
 import { IdlInstruction } from "@coral-xyz/anchor/dist/cjs/idl";
 
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// per second rates, all values scaled by 1e18
+export const DEFAULT_ADAPTIVE_CURVE = {
+  targetUtilization: new anchor.BN("900000000000000000"),
+  curveSteepness: new anchor.BN("4000000000000000000"),
+  adjustmentSpeed: new anchor.BN("1585489599188"),
+  minRateAtTarget: new anchor.BN("31709791"),
+  maxRateAtTarget: new anchor.BN("63419583967"),
+};
+
+export const DEFAULT_CURVE_LIMITS = {
+  minTargetUtilization: new anchor.BN("500000000000000000"),
+  maxTargetUtilization: new anchor.BN("950000000000000000"),
+  maxCurveSteepness: new anchor.BN("10000000000000000000"),
+  maxAdjustmentSpeed: new anchor.BN("3170979198376"),
+  minRateAtTarget: new anchor.BN("31709791"),
+  maxRateAtTarget: new anchor.BN("63419583967"),
+};
+
+// gap between the max borrow ltv and the liquidation ltv when a test sets none
+export const DEFAULT_LIQUIDATION_LTV_BUFFER = new anchor.BN(0.05 * 1e9);
+
+// max age in seconds, confidence and floor in bps of price
+export const DEFAULT_ORACLE_CONFIG = {
+  maxAge: new anchor.BN(3600),
+  maxConfBps: new anchor.BN(10_000),
+  priceFloorBps: new anchor.BN(100),
+  pythVerificationLevel: { full: {} },
+  minSbResponses: 1,
+};
+
 export class MarketFixture {
   public marketAcc: marketAccountFixture;
   public program: Program<Markets>;
@@ -33,7 +65,7 @@ export class MarketFixture {
 
     this.marketAcc = new marketAccountFixture(
       "market",
-      deriveMarketAddress(_quoteMint, _collateralMint, this.collateral._ltvFactor, this.collateral.getOracleId(), _program.programId),
+      deriveMarketAddress(_quoteMint, _collateralMint, this.collateral._maxBorrowLtv, this.collateral.getOracleId(), _program.programId),
       _program,
     );
     this.program = _program;
@@ -51,26 +83,39 @@ export class MarketFixture {
 
   async createAndSetAuthority({
     user,
+    liquidationLtv,
+    oracleConfig,
+    fallbackOracle,
+    irm,
+    supplyCap,
+    borrowCap,
   }: {
     user: UserFixture;
+    liquidationLtv?: anchor.BN;
+    oracleConfig?: any;
+    fallbackOracle?: any;
+    irm?: any;
+    supplyCap?: anchor.BN;
+    borrowCap?: anchor.BN;
   }): Promise<void> {
-    await this.updateRecipient({
-      user: this.configAuthority,
-      new_recipient: this.configFeeRecipient,
-    });
-    await this.updateAuthority({
+    await this.initializeConfig({
       user: this.configAuthority,
-      new_authority: this.configAuthority,
     });
 
     await this.createCustom({
       user,
       collateralSymbol: this.collateral.symbol,
-      ltvFactor: this.collateral._ltvFactor,
+      maxBorrowLtv: this.collateral._maxBorrowLtv,
+      liquidationLtv,
       quoteMint: this.quoteMint,
       vaultAtaQuote: this.get_ata(this.quoteMint),
       collateralMint: this.collateral.collateralMint,
       vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
+      oracleConfig,
+      fallbackOracle,
+      irm,
+      supplyCap,
+      borrowCap,
     });
   }
 
@@ -82,7 +127,7 @@ export class MarketFixture {
     await this.createCustom({
       user,
       collateralSymbol: this.collateral.symbol,
-      ltvFactor: this.collateral._ltvFactor,
+      maxBorrowLtv: this.collateral._maxBorrowLtv,
       quoteMint: this.quoteMint,
       vaultAtaQuote: this.get_ata(this.quoteMint),
       collateralMint: this.collateral.collateralMint,
@@ -92,28 +137,49 @@ export class MarketFixture {
 
   async createCustom({
     user,
-    ltvFactor,
+    maxBorrowLtv,
+    liquidationLtv,
     quoteMint,
     vaultAtaQuote,
     collateralMint,
     vaultAtaCollateral,
+    quoteTokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID,
+    collateralTokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID,
+    oracleConfig = DEFAULT_ORACLE_CONFIG,
+    fallbackOracle = null,
+    irm = { adaptive: { curve: DEFAULT_ADAPTIVE_CURVE } },
+    supplyCap = new anchor.BN(0),
+    borrowCap = new anchor.BN(0),
   }: {
     user: UserFixture;
     collateralSymbol: SupportedCollateral;
-    ltvFactor: anchor.BN;
+    maxBorrowLtv: anchor.BN;
+    liquidationLtv?: anchor.BN;
     quoteMint: PublicKey;
     vaultAtaQuote: PublicKey;
     collateralMint: PublicKey;
     vaultAtaCollateral: PublicKey;
+    quoteTokenProgram?: PublicKey;
+    collateralTokenProgram?: PublicKey;
+    oracleConfig?: any;
+    fallbackOracle?: any;
+    irm?: any;
+    supplyCap?: anchor.BN;
+    borrowCap?: anchor.BN;
   }): Promise<void> {
 
-    let source = this.collateral.getOracleSource() === OracleSource.PythPull ? { pythPull: {} } : { switchboardPull: {} }
-
     await this.program.methods
       .createMarket({
         oracleId: this.collateral.getOracleId(),
-        ltvFactor,
-        oracleSource: source,
+        maxBorrowLtv,
+        liquidationLtv: liquidationLtv ?? maxBorrowLtv.add(DEFAULT_LIQUIDATION_LTV_BUFFER),
+        oracleSource: this.collateral.getOracleSourceArg(),
+        quoteOracleId: this.collateral.getQuoteOracleId(),
+        oracleConfig,
+        fallbackOracle,
+        irm,
+        supplyCap,
+        borrowCap,
       })
       .accounts({
         user: user.key.publicKey,
@@ -124,7 +190,8 @@ export class MarketFixture {
         vaultAtaQuote,
         vaultAtaCollateral,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        quoteTokenProgram,
+        collateralTokenProgram,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([user.key.payer])
@@ -133,7 +200,7 @@ export class MarketFixture {
     const marketAccountData = await this.marketAcc.get_data();
     assert.equal(marketAccountData.quoteMint.toBase58(), quoteMint.toBase58());
     assert.equal(marketAccountData.collateralMint.toBase58(), collateralMint.toBase58());
-    assert.equal(marketAccountData.ltvFactor.toString(), ltvFactor.toString());
+    assert.equal(marketAccountData.maxBorrowLtv.toString(), maxBorrowLtv.toString());
   }
 
   async deposit({
@@ -273,6 +340,8 @@ export class MarketFixture {
         tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([user.key.payer])
@@ -285,12 +354,14 @@ export class MarketFixture {
     shares,
     owner,
     recipient,
+    fallbackOracleAi = this.collateral.getFallbackOracleAccount(),
   }: {
     user: UserFixture;
     amount: anchor.BN;
     shares: anchor.BN;
     owner: UserFixture;
     recipient: UserFixture;
+    fallbackOracleAi?: PublicKey | null;
   }): Promise<void> {
 
     await this.program.methods
@@ -315,6 +386,8 @@ export class MarketFixture {
         tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([user.key.payer])
@@ -363,35 +436,300 @@ export class MarketFixture {
     borrower,
     collateralAmount,
     repayShares,
+    callback,
+    auction = false,
   }: {
     user: UserFixture;
     borrower: PublicKey;
     collateralAmount: anchor.BN;
     repayShares: anchor.BN;
+    auction?: boolean;
+    callback?: {
+      programId: PublicKey;
+      accounts: anchor.web3.AccountMeta[];
+      signers: anchor.web3.Keypair[];
+    };
   }): Promise<void> {
 
+    const remainingAccounts = callback
+      ? [{ pubkey: callback.programId, isSigner: false, isWritable: false }, ...callback.accounts]
+      : [];
+
     const tx = await this.program.methods
       .liquidate({
         borrower,
         collateralAmount,
         repayShares,
+        data: Buffer.from([]),
       })
       .accounts({
         user: user.key.publicKey,
         config: this.get_config().key,
         market: this.marketAcc.key,
         borrowerShares: this.get_borrower_shares(borrower).key,
+        liquidationState: auction ? this.get_liquidation_state(borrower).key : null,
         quoteMint: this.quoteMint,
         vaultAtaQuote: this.get_ata(this.quoteMint),
         userAtaQuote: user.quoteAta,
         collateralMint: this.collateral.collateralMint,
         vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
         userAtaCollateral: user.get_ata(this.collateral.collateralMint),
-        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        collateralTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([user.key.payer, ...(callback ? callback.signers : [])])
+      .rpc();
+  }
+
+  async updateLiquidationState({
+    user,
+    borrower,
+  }: {
+    user: UserFixture;
+    borrower: PublicKey;
+  }): Promise<void> {
+    await this.program.methods
+      .updateLiquidationState({
+        borrower,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(borrower).key,
+        liquidationState: this.get_liquidation_state(borrower).key,
+        collateralMint: this.collateral.collateralMint,
+        oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async preLiquidate({
+    user,
+    borrower,
+    collateralAmount,
+    repayShares,
+  }: {
+    user: UserFixture;
+    borrower: PublicKey;
+    collateralAmount: anchor.BN;
+    repayShares: anchor.BN;
+  }): Promise<void> {
+    await this.program.methods
+      .preLiquidate({
+        borrower,
+        collateralAmount,
+        repayShares,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(borrower).key,
+        preLiquidationAuthorization: this.get_pre_liquidation_authorization(borrower).key,
+        quoteMint: this.quoteMint,
+        vaultAtaQuote: this.get_ata(this.quoteMint),
+        userAtaQuote: user.quoteAta,
+        collateralMint: this.collateral.collateralMint,
+        vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
+        userAtaCollateral: user.get_ata(this.collateral.collateralMint),
+        quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        collateralTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async setPreLiquidationAuthorization({
+    user,
+    enabled,
+  }: {
+    user: UserFixture;
+    enabled: boolean;
+  }): Promise<void> {
+    await this.program.methods
+      .setPreLiquidationAuthorization({
+        enabled,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        market: this.marketAcc.key,
+        preLiquidationAuthorization: this.get_pre_liquidation_authorization(user.key.publicKey).key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async deleverage({
+    user,
+    owner,
+    collateralAmount,
+    minQuoteOut,
+    data = Buffer.from([]),
+    callback,
+  }: {
+    user: UserFixture;
+    owner: UserFixture;
+    collateralAmount: anchor.BN;
+    minQuoteOut: anchor.BN;
+    data?: Buffer;
+    callback: {
+      programId: PublicKey;
+      accounts: anchor.web3.AccountMeta[];
+      signers: anchor.web3.Keypair[];
+    };
+  }): Promise<void> {
+    await this.program.methods
+      .deleverage({
+        owner: owner.key.publicKey,
+        collateralAmount,
+        minQuoteOut,
+        data,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        positionDelegate: this.get_position_delegate(owner.key.publicKey).key,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(owner.key.publicKey).key,
+        quoteMint: this.quoteMint,
+        vaultAtaQuote: this.get_ata(this.quoteMint),
+        userAtaQuote: user.quoteAta,
+        collateralMint: this.collateral.collateralMint,
+        vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
+        userAtaCollateral: user.get_ata(this.collateral.collateralMint),
+        quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        collateralTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: callback.programId, isSigner: false, isWritable: false },
+        ...callback.accounts,
+      ])
+      .signers([user.key.payer, ...callback.signers])
+      .rpc();
+  }
+
+  async leverage({
+    user,
+    collateralAmount,
+    quoteAmount,
+    minCollateralOut,
+    maxLtv,
+    data = Buffer.from([]),
+    callback,
+  }: {
+    user: UserFixture;
+    collateralAmount: anchor.BN;
+    quoteAmount: anchor.BN;
+    minCollateralOut: anchor.BN;
+    maxLtv: anchor.BN;
+    data?: Buffer;
+    callback: {
+      programId: PublicKey;
+      accounts: anchor.web3.AccountMeta[];
+      signers: anchor.web3.Keypair[];
+    };
+  }): Promise<void> {
+    await this.program.methods
+      .leverage({
+        collateralAmount,
+        quoteAmount,
+        minCollateralOut,
+        maxLtv,
+        data,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(user.key.publicKey).key,
+        quoteMint: this.quoteMint,
+        vaultAtaQuote: this.get_ata(this.quoteMint),
+        userAtaQuote: user.quoteAta,
+        collateralMint: this.collateral.collateralMint,
+        vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
+        userAtaCollateral: user.get_ata(this.collateral.collateralMint),
+        quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        collateralTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
         systemProgram: anchor.web3.SystemProgram.programId,
       })
+      .remainingAccounts([
+        { pubkey: callback.programId, isSigner: false, isWritable: false },
+        ...callback.accounts,
+      ])
+      .signers([user.key.payer, ...callback.signers])
+      .rpc();
+  }
+
+  async flashLoan({
+    user,
+    quoteAmount,
+    collateralAmount,
+    repay = true,
+  }: {
+    user: UserFixture;
+    quoteAmount: anchor.BN;
+    collateralAmount: anchor.BN;
+    repay?: boolean;
+  }): Promise<void> {
+
+    const accounts = {
+      user: user.key.publicKey,
+      market: this.marketAcc.key,
+      quoteMint: this.quoteMint,
+      vaultAtaQuote: this.get_ata(this.quoteMint),
+      userAtaQuote: user.quoteAta,
+      collateralMint: this.collateral.collateralMint,
+      vaultAtaCollateral: this.get_ata(this.collateral.collateralMint),
+      userAtaCollateral: user.get_ata(this.collateral.collateralMint),
+      quoteTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      collateralTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+    };
+
+    const repayInstruction = await this.program.methods
+      .flashRepay({
+        quoteAmount,
+        collateralAmount,
+      })
+      .accounts(accounts)
+      .instruction();
+
+    await this.program.methods
+      .flashLoan({
+        quoteAmount,
+        collateralAmount,
+        data: Buffer.from([]),
+      })
+      .accounts({
+        ...accounts,
+        instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .postInstructions(repay ? [repayInstruction] : [])
       .signers([user.key.payer])
       .rpc();
   }
@@ -449,6 +787,52 @@ export class MarketFixture {
       .rpc();
   }
 
+  async closeLenderPosition({
+    user,
+  }: {
+    user: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .closeLenderPosition()
+      .accounts({
+        user: user.key.publicKey,
+        market: this.marketAcc.key,
+        lenderShares: this.get_lender_shares(user.key.publicKey).key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async closeBorrowerPosition({
+    user,
+  }: {
+    user: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .closeBorrowerPosition()
+      .accounts({
+        user: user.key.publicKey,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(user.key.publicKey).key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async closeDelegate({
+    user,
+  }: {
+    user: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .closeDelegate()
+      .accounts({
+        user: user.key.publicKey,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
   async updateFee({
     user,
     feeFactor,
@@ -469,6 +853,26 @@ export class MarketFixture {
       .rpc();
   }
 
+  async updateLiquidationFee({
+    user,
+    liquidationFeeFactor,
+  }: {
+    user: UserFixture;
+    liquidationFeeFactor: anchor.BN;
+  }): Promise<void> {
+    await this.program.methods
+      .updateLiquidationFee({
+        newLiquidationFeeFactor: liquidationFeeFactor,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
   async updateRecipient({
     user,
     new_recipient,
@@ -489,7 +893,38 @@ export class MarketFixture {
       .rpc();
   }
 
-  async updateAuthority({
+  async initializeConfig({
+    user,
+    upgradeAuthority = user,
+    authority = this.configAuthority,
+    feeRecipient = this.configFeeRecipient,
+    curveLimits = DEFAULT_CURVE_LIMITS,
+  }: {
+    user: UserFixture;
+    upgradeAuthority?: UserFixture;
+    authority?: UserFixture;
+    feeRecipient?: UserFixture;
+    curveLimits?: typeof DEFAULT_CURVE_LIMITS;
+  }): Promise<void> {
+    const programData = await this.setProgramData(upgradeAuthority);
+
+    await this.program.methods
+      .initializeConfig({
+        authority: authority.key.publicKey,
+        feeRecipient: feeRecipient.key.publicKey,
+        curveLimits,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        programData,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async proposeAuthority({
     user,
     new_authority,
   }: {
@@ -497,7 +932,7 @@ export class MarketFixture {
     new_authority: UserFixture;
   }): Promise<void> {
     await this.program.methods
-      .updateAuthority({
+      .proposeAuthority({
         newAuthority: new_authority.key.publicKey,
       })
       .accounts({
@@ -509,6 +944,337 @@ export class MarketFixture {
       .rpc();
   }
 
+  async acceptAuthority({
+    user,
+  }: {
+    user: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .acceptAuthority()
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async cancelAuthority({
+    user,
+  }: {
+    user: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .cancelAuthority()
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updateCurveLimits({
+    user,
+    curveLimits,
+  }: {
+    user: UserFixture;
+    curveLimits: typeof DEFAULT_CURVE_LIMITS;
+  }): Promise<void> {
+    await this.program.methods
+      .updateCurveLimits({
+        newCurveLimits: curveLimits,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updateGuardian({
+    user,
+    newGuardian,
+  }: {
+    user: UserFixture;
+    newGuardian: UserFixture;
+  }): Promise<void> {
+    await this.program.methods
+      .updateGuardian({
+        newGuardian: newGuardian.key.publicKey,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async setMarketPause({
+    user,
+    deposit = false,
+    borrow = false,
+    withdraw = false,
+    withdrawCollateral = false,
+    liquidate = false,
+  }: {
+    user: UserFixture;
+    deposit?: boolean;
+    borrow?: boolean;
+    withdraw?: boolean;
+    withdrawCollateral?: boolean;
+    liquidate?: boolean;
+  }): Promise<void> {
+    await this.program.methods
+      .setMarketPause({
+        paused: { deposit, borrow, withdraw, withdrawCollateral, liquidate },
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async setFallbackOracle({
+    user,
+    fallbackOracle,
+  }: {
+    user: UserFixture;
+    fallbackOracle: any;
+  }): Promise<void> {
+    await this.program.methods
+      .setFallbackOracle({
+        fallbackOracle,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updateMarketCaps({
+    user,
+    supplyCap,
+    borrowCap,
+  }: {
+    user: UserFixture;
+    supplyCap: anchor.BN;
+    borrowCap: anchor.BN;
+  }): Promise<void> {
+    await this.program.methods
+      .updateMarketCaps({
+        supplyCap,
+        borrowCap,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updateLiquidationAuction({
+    user,
+    duration,
+  }: {
+    user: UserFixture;
+    duration: anchor.BN;
+  }): Promise<void> {
+    await this.program.methods
+      .updateLiquidationAuction({
+        duration,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updateLiquidationLimits({
+    user,
+    limits,
+  }: {
+    user: UserFixture;
+    limits: any;
+  }): Promise<void> {
+    await this.program.methods
+      .updateLiquidationLimits({
+        limits,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async updatePreLiquidation({
+    user,
+    preLiquidation,
+  }: {
+    user: UserFixture;
+    preLiquidation: any;
+  }): Promise<void> {
+    await this.program.methods
+      .updatePreLiquidation({
+        preLiquidation,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  // writes accounts as they were laid out before versioning
+  async setLegacyConfig(config: Omit<LegacyConfig, "bump">): Promise<void> {
+    const [configKey, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      this.program.programId
+    );
+    await this.setLegacyAccount(configKey, encodeLegacyConfig({ ...config, bump }));
+  }
+
+  async setLegacyMarket(market: Omit<LegacyMarket, "bump">): Promise<void> {
+    const [, bump] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("market"),
+        market.quoteMint.toBuffer(),
+        market.collateralMint.toBuffer(),
+        Buffer.from(market.ltvFactor.toArray("le", 8)),
+        market.oracleId.toBuffer(),
+      ],
+      this.program.programId
+    );
+    await this.setLegacyAccount(this.marketAcc.key, encodeLegacyMarket({ ...market, bump }));
+  }
+
+  // bankrun loads the program without a program data account, so write the one an
+  // upgradeable deployment would have. Only its metadata is read.
+  private async setProgramData(upgradeAuthority: UserFixture): Promise<PublicKey> {
+    const [programData] = PublicKey.findProgramAddressSync(
+      [this.program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    );
+
+    // UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }
+    const data = Buffer.alloc(45);
+    data.writeUInt32LE(3, 0);
+    data.writeBigUInt64LE(BigInt(0), 4);
+    data.writeUInt8(1, 12);
+    upgradeAuthority.key.publicKey.toBuffer().copy(data, 13);
+
+    const rent = await this.provider.context.banksClient.getRent();
+    create_custom_account(
+      this.provider.context,
+      programData,
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
+      Number(rent.minimumBalance(BigInt(data.length))),
+      data,
+      0,
+    );
+
+    return programData;
+  }
+
+  private async setLegacyAccount(key: PublicKey, data: Buffer): Promise<void> {
+    const rent = await this.provider.context.banksClient.getRent();
+    create_custom_account(
+      this.provider.context,
+      key,
+      this.program.programId,
+      Number(rent.minimumBalance(BigInt(data.length))),
+      data,
+      0,
+    );
+  }
+
+  async migrateConfig({
+    user,
+    curveLimits = DEFAULT_CURVE_LIMITS,
+  }: {
+    user: UserFixture;
+    curveLimits?: any;
+  }): Promise<void> {
+    await this.program.methods
+      .migrateConfig({
+        curveLimits,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async migrateMarket({
+    user,
+    oracleConfig = DEFAULT_ORACLE_CONFIG,
+    irm = { adaptive: { curve: DEFAULT_ADAPTIVE_CURVE } },
+    liquidationLtv,
+  }: {
+    user: UserFixture;
+    oracleConfig?: any;
+    irm?: any;
+    liquidationLtv: anchor.BN;
+  }): Promise<void> {
+    await this.program.methods
+      .migrateMarket({
+        oracleConfig,
+        irm,
+        liquidationLtv,
+      })
+      .accounts({
+        user: user.key.publicKey,
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+      })
+      .signers([user.key.payer])
+      .rpc();
+  }
+
+  async viewPositionHealth(owner: PublicKey): Promise<[anchor.BN, anchor.BN]> {
+    const result = await this.program.methods
+      .viewPositionHealth(owner)
+      .accounts({
+        config: this.get_config().key,
+        market: this.marketAcc.key,
+        borrowerShares: this.get_borrower_shares(owner).key,
+        oracleAi: this.collateral.getOracleAccount(),
+        quoteOracleAi: this.collateral.getQuoteOracleAccount(),
+        fallbackOracleAi: this.collateral.getFallbackOracleAccount(),
+      })
+      .signers([this.provider.wallet.payer])
+      .view();
+
+    return [
+      new anchor.BN(result[0].toString()),
+      new anchor.BN(result[1].toString()),
+    ];
+  }
+
   async accrueInterest(): Promise<void> {
     await this.program.methods
       .accrueInterest()
@@ -620,6 +1386,38 @@ export class MarketFixture {
   }
 
 
+  public get_liquidation_state(userKey: PublicKey): AccountFixture {
+    let liquidationStateKey = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("liquidation_state"),
+        this.marketAcc.key.toBuffer(),
+        userKey.toBuffer(),
+      ],
+      this.program.programId
+    )[0];
+    return new AccountFixture(
+      "liquidationState",
+      liquidationStateKey,
+      this.program
+    );
+  }
+
+  public get_pre_liquidation_authorization(userKey: PublicKey): AccountFixture {
+    let preLiquidationKey = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pre_liquidation"),
+        this.marketAcc.key.toBuffer(),
+        userKey.toBuffer(),
+      ],
+      this.program.programId
+    )[0];
+    return new AccountFixture(
+      "preLiquidationAuthorization",
+      preLiquidationKey,
+      this.program
+    );
+  }
+
   public get_position_delegate(userKey: PublicKey): AccountFixture {
     let positionDelegateKey = PublicKey.findProgramAddressSync(
       [
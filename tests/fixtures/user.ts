@@ -91,6 +91,10 @@ export class UserFixture {
     return (await this.get_balance(this.collateralAta)).amount
   }
 
+  public async get_sol_balance(): Promise<bigint> {
+    return await this.provider.context.banksClient.getBalance(this.key.publicKey);
+  }
+
   public get_ata(mint: PublicKey): PublicKey {
     return anchor.utils.token.associatedAddress({
       mint,
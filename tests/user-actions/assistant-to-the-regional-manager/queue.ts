@@ -29,7 +29,7 @@ describe("queue", () => {
 
     // market = await test.createMarket({
     //   symbol: "BONK",
-    //   ltvFactor: new anchor.BN(0),
+    //   maxBorrowLtv: new anchor.BN(0),
     //   price: new anchor.BN(100 * 1e9),
     //   conf: new anchor.BN(100 / 10 * 1e9),
     //   expo: -9,
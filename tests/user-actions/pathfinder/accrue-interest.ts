@@ -35,7 +35,7 @@ describe("Accrue Interest", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -9,
@@ -182,6 +182,74 @@ describe("Accrue Interest", () => {
     );
   });
 
+  it("correctly for year with a fixed rate model", async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(1_000_000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(1_000_000 * 1e9),
+      new anchor.BN(1_000_000 * 1e9)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(100 / 10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    // 10% per year, per second scaled by WAD
+    await market.createAndSetAuthority({
+      user: larry,
+      irm: { fixed: { rate: new anchor.BN(3_170_979_198) } },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * LAMPORTS_PER_SOL),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(100 * LAMPORTS_PER_SOL),
+      owner: bob,
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(500 * LAMPORTS_PER_SOL),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    const beforeTotalBorrows = await market.marketAcc.getTotalBorrows();
+
+    await test.moveTimeForward(365 * 24 * 3600);
+
+    await market.accrueInterest();
+
+    const afterTotalBorrows = await market.marketAcc.getTotalBorrows();
+
+    // the rate does not depend on the 50% utilization
+    assert.equal(
+      afterTotalBorrows.sub(beforeTotalBorrows).toNumber(),
+      52_583_333_326
+    );
+  });
+
   it("updates last accrual timestamp", async () => {
     const beforeTotalBorrows = await market.marketAcc.getTotalBorrows();
 
@@ -221,7 +289,7 @@ describe("Accrue Interest", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e6),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e6),
       conf: new anchor.BN(10 * 1e6),
       expo: -6,
@@ -296,7 +364,7 @@ describe("Accrue Interest", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -9,
@@ -370,7 +438,7 @@ describe("Accrue Interest", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e6),
       conf: new anchor.BN(10 * 1e6),
       expo: -6,
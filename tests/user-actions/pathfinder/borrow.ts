@@ -34,7 +34,7 @@ describe("User Borrow", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(10 * 1e9), // upperbound: 110 * 1e9, lowerbound: 90 * 1e9
       expo: -9,
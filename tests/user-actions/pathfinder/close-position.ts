@@ -0,0 +1,155 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+describe("Close Position", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let larry: UserFixture;
+  let bob: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1000 * 1e9)
+    );
+
+    let futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(100 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      owner: bob,
+      amount: new anchor.BN(100 * 1e9),
+    });
+  });
+
+  it("closes an empty lender position and refunds rent", async () => {
+    const lenderShares = market.get_lender_shares(larry.key.publicKey);
+    const { shares } = await lenderShares.get_data();
+
+    await market.withdraw({
+      user: larry,
+      owner: larry,
+      recipient: larry,
+      amount: new anchor.BN(0),
+      shares,
+    });
+
+    const rent = await larry.provider.context.banksClient.getBalance(lenderShares.key);
+    const balanceBefore = await larry.get_sol_balance();
+
+    await market.closeLenderPosition({ user: larry });
+
+    const balanceAfter = await larry.get_sol_balance();
+    assert.equal(await lenderShares.get_data(), undefined);
+    assert.ok(balanceAfter > balanceBefore);
+    assert.ok(balanceAfter - balanceBefore <= rent);
+  });
+
+  it("fails to close a lender position with shares", async () => {
+    await assert.rejects(
+      async () => {
+        await market.closeLenderPosition({ user: larry });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Position is not empty");
+        return true;
+      }
+    );
+  });
+
+  it("fails to close a borrower position with collateral", async () => {
+    await assert.rejects(
+      async () => {
+        await market.closeBorrowerPosition({ user: bob });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Position is not empty");
+        return true;
+      }
+    );
+  });
+
+  it("fails to close a borrower position with debt", async () => {
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(1 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await market.withdrawCollateral({
+      user: bob,
+      owner: bob,
+      recipient: bob,
+      amount: new anchor.BN(90 * 1e9),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.closeBorrowerPosition({ user: bob });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Position is not empty");
+        return true;
+      }
+    );
+  });
+
+  it("closes an empty borrower position", async () => {
+    await market.withdrawCollateral({
+      user: bob,
+      owner: bob,
+      recipient: bob,
+      amount: new anchor.BN(100 * 1e9),
+    });
+
+    await market.closeBorrowerPosition({ user: bob });
+
+    const borrowerShares = market.get_borrower_shares(bob.key.publicKey);
+    assert.equal(await borrowerShares.get_data(), undefined);
+  });
+
+  it("closes a position delegate", async () => {
+    await market.updateDelegate({ user: larry, newDelegate: bob });
+
+    await market.closeDelegate({ user: larry });
+
+    const positionDelegate = market.get_position_delegate(larry.key.publicKey);
+    assert.equal(await positionDelegate.get_data(), undefined);
+  });
+});
@@ -1,8 +1,9 @@
 import * as anchor from "@coral-xyz/anchor";
 import { TestUtils } from '../../utils';
-import { MarketFixture } from '../../fixtures';
+import { DEFAULT_ADAPTIVE_CURVE, MarketFixture } from '../../fixtures';
 import assert from 'assert';
 import { UserFixture } from "../../fixtures";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 
 describe("Create Market Operations", () => {
   let test: TestUtils;
@@ -39,7 +40,7 @@ describe("Create Market Operations", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0),
+      maxBorrowLtv: new anchor.BN(0),
       price: new anchor.BN(100 * 1e5),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -5,
@@ -58,11 +59,103 @@ describe("Create Market Operations", () => {
     assert.equal(deposits.toNumber(), 0);
   });
 
+  it("fails to create a market with an invalid kink", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0),
+      price: new anchor.BN(100 * 1e5),
+      conf: new anchor.BN(100 / 10 * 1e9),
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.createAndSetAuthority({
+          user: larry,
+          irm: {
+            linearKink: {
+              baseRate: new anchor.BN(0),
+              slopeLow: new anchor.BN(1_000_000_000),
+              slopeHigh: new anchor.BN(10_000_000_000),
+              kinkUtilization: new anchor.BN("1000000000000000000"),
+            },
+          },
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid interest rate model");
+        return true;
+      }
+    );
+  });
+
+  it("fails to create a market with a max borrow ltv at the liquidation ltv", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e5),
+      conf: new anchor.BN(100 / 10 * 1e9),
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.createAndSetAuthority({
+          user: larry,
+          liquidationLtv: new anchor.BN(0.8 * 1e9),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Max borrow ltv must be below the liquidation ltv");
+        return true;
+      }
+    );
+  });
+
+  it("fails to create a market with a target utilization outside the curve limits", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0),
+      price: new anchor.BN(100 * 1e5),
+      conf: new anchor.BN(100 / 10 * 1e9),
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.createAndSetAuthority({
+          user: larry,
+          irm: {
+            adaptive: {
+              curve: {
+                ...DEFAULT_ADAPTIVE_CURVE,
+                targetUtilization: new anchor.BN("990000000000000000"),
+              },
+            },
+          },
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid interest rate model");
+        return true;
+      }
+    );
+  });
+
   it("fails to create a duplicate market", async () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0),
+      maxBorrowLtv: new anchor.BN(0),
       price: new anchor.BN(100 * 1e5),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -5,
@@ -84,6 +177,41 @@ describe("Create Market Operations", () => {
     );
   });
 
+  it("fails to create a market with a token program that does not own the mint", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0),
+      price: new anchor.BN(100 * 1e5),
+      conf: new anchor.BN(100 / 10 * 1e9),
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.initializeConfig({ user: market.configAuthority });
+
+    await assert.rejects(
+      async () => {
+        await market.createCustom({
+          user: larry,
+          collateralSymbol: market.collateral.symbol,
+          maxBorrowLtv: market.collateral._maxBorrowLtv,
+          quoteMint: market.quoteMint,
+          vaultAtaQuote: market.get_ata(market.quoteMint),
+          collateralMint: market.collateral.collateralMint,
+          vaultAtaCollateral: market.get_ata(market.collateral.collateralMint),
+          quoteTokenProgram: TOKEN_2022_PROGRAM_ID,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        // the quote mint is owned by the legacy token program
+        return true;
+      },
+      "Expected market creation to fail with a mismatched token program"
+    );
+  });
+
   it("fails to create a market where the collateral mint is the same as the quote mint", async () => {
   });
 });
\ No newline at end of file
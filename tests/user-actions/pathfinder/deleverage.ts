@@ -0,0 +1,262 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+const MOCK_SWAP_IDL = require("../../../target/idl/mock_swap.json");
+const MOCK_SWAP_PROGRAM_ID = new anchor.web3.PublicKey(MOCK_SWAP_IDL.address);
+
+describe("Deleverage", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let borrower: UserFixture;
+  let lender: UserFixture;
+  let pool: UserFixture;  // AMM buying the released collateral
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    lender = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    borrower = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1000 * 1e9)
+    );
+
+    pool = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(8 * 1e8), // 80% LTV
+      price: new anchor.BN(1e5), // $1.00
+      conf: new anchor.BN(1 * 10 ** 4), // $0.01 confidence interval
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: lender });
+
+    await market.deposit({
+      user: lender,
+      amount: new anchor.BN(1000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: lender,
+    });
+
+    await market.depositCollateral({
+      user: borrower,
+      amount: new anchor.BN(100 * 1e9),
+      owner: borrower
+    });
+
+    await market.borrow({
+      user: borrower,
+      amount: new anchor.BN(70 * 1e9),
+      shares: new anchor.BN(0),
+      owner: borrower,
+      recipient: borrower,
+    });
+  });
+
+  // pool pays `quoteOut` for the collateral released to `user`
+  function swap(quoteOut: anchor.BN, user: UserFixture = borrower) {
+    return {
+      data: quoteOut.toArrayLike(Buffer, "le", 8),
+      callback: {
+        programId: MOCK_SWAP_PROGRAM_ID,
+        accounts: [
+          { pubkey: user.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: pool.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: user.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: user.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: pool.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: pool.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: anchor.utils.token.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        signers: [pool.key.payer],
+      },
+    };
+  }
+
+  it("repays debt with swapped collateral", async () => {
+    const initialShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    const initialQuote = await borrower.get_quo_balance();
+
+    await market.deleverage({
+      user: borrower,
+      owner: borrower,
+      collateralAmount: new anchor.BN(30 * 1e9),
+      minQuoteOut: new anchor.BN(29 * 1e9),
+      ...swap(new anchor.BN(29 * 1e9)),
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(finalShares.collateralAmount.toString(), (70 * 1e9).toString());
+    assert.ok(finalShares.borrowShares.lt(initialShares.borrowShares));
+
+    // all proceeds went to the debt
+    assert.equal(await borrower.get_quo_balance(), initialQuote);
+    assert.equal(await pool.get_col_balance(), BigInt(30 * 1e9));
+
+    const marketData = await market.marketAcc.get_data();
+    assert.equal(marketData.totalCollateral.toString(), (70 * 1e9).toString());
+  });
+
+  it("closes the position and keeps the surplus", async () => {
+    const initialQuote = await borrower.get_quo_balance();
+
+    await market.deleverage({
+      user: borrower,
+      owner: borrower,
+      collateralAmount: new anchor.BN(80 * 1e9),
+      minQuoteOut: new anchor.BN(0),
+      ...swap(new anchor.BN(80 * 1e9)),
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(finalShares.borrowShares.toNumber(), 0);
+    assert.equal(finalShares.collateralAmount.toString(), (20 * 1e9).toString());
+
+    // 80 in, a little over 70 of debt repaid
+    const surplus = (await borrower.get_quo_balance()) - initialQuote;
+    assert.ok(
+      surplus > BigInt(9_990_000_000) && surplus <= BigInt(10 * 1e9),
+      "Borrower should keep the proceeds above the debt"
+    );
+  });
+
+  it("deleverages near the ltv limit where withdrawals fail", async () => {
+    // $0.88 low end puts the position at ~79.5% ltv
+    await market.collateral.setPrice({
+      price: new anchor.BN(89 * 1e3),
+      conf: new anchor.BN(1 * 10 ** 3),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.withdrawCollateral({
+          user: borrower,
+          amount: new anchor.BN(5 * 1e9),
+          owner: borrower,
+          recipient: borrower,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+
+    await market.deleverage({
+      user: borrower,
+      owner: borrower,
+      collateralAmount: new anchor.BN(20 * 1e9),
+      minQuoteOut: new anchor.BN(17 * 1e9),
+      ...swap(new anchor.BN(17.6 * 1e9)),
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(finalShares.collateralAmount.toString(), (80 * 1e9).toString());
+  });
+
+  it("fails below the minimum quote out", async () => {
+    await assert.rejects(
+      async () => {
+        await market.deleverage({
+          user: borrower,
+          owner: borrower,
+          collateralAmount: new anchor.BN(30 * 1e9),
+          minQuoteOut: new anchor.BN(30 * 1e9),
+          ...swap(new anchor.BN(29 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Swap returned less than the minimum output");
+        return true;
+      }
+    );
+  });
+
+  it("fails if the position ends insolvent", async () => {
+    await assert.rejects(
+      async () => {
+        await market.deleverage({
+          user: borrower,
+          owner: borrower,
+          collateralAmount: new anchor.BN(50 * 1e9),
+          minQuoteOut: new anchor.BN(0),
+          ...swap(new anchor.BN(10 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+  });
+
+  it("fails while collateral withdrawals are paused", async () => {
+    await market.setMarketPause({
+      user: futarchy,
+      withdrawCollateral: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.deleverage({
+          user: borrower,
+          owner: borrower,
+          collateralAmount: new anchor.BN(30 * 1e9),
+          minQuoteOut: new anchor.BN(0),
+          ...swap(new anchor.BN(30 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+  });
+
+  it("fails for someone else's position", async () => {
+    await assert.rejects(
+      async () => {
+        await market.deleverage({
+          user: lender,
+          owner: borrower,
+          collateralAmount: new anchor.BN(10 * 1e9),
+          minQuoteOut: new anchor.BN(0),
+          ...swap(new anchor.BN(10 * 1e9), lender),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Unauthorized delegate");
+        return true;
+      }
+    );
+  });
+});
@@ -32,7 +32,7 @@ describe("Deposit", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0),
+      maxBorrowLtv: new anchor.BN(0),
       price: new anchor.BN(100 * 1e5),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -5,
@@ -118,7 +118,7 @@ describe("Deposit", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0),
+      maxBorrowLtv: new anchor.BN(0),
       price: new anchor.BN(100 * 1e5),
       conf: new anchor.BN(100 / 10 * 1e9),
       expo: -5,
@@ -0,0 +1,157 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+describe("Flash Loan", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let larry: UserFixture;
+  let bob: UserFixture;
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    larry = await test.createUser(
+      new anchor.BN(1_000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    bob = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1_000 * 1e9),
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(500 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry,
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(100 * 1e9),
+      owner: bob,
+    });
+  });
+
+  it("lends quote and collateral within a transaction", async () => {
+    const initialQuoteVault = await bob.get_balance(market.get_ata(market.quoteMint));
+    const initialCollateralVault = await bob.get_balance(market.get_ata(market.collateral.collateralMint));
+
+    await market.flashLoan({
+      user: bob,
+      quoteAmount: new anchor.BN(500 * 1e9),
+      collateralAmount: new anchor.BN(100 * 1e9),
+    });
+
+    const finalQuoteVault = await bob.get_balance(market.get_ata(market.quoteMint));
+    const finalCollateralVault = await bob.get_balance(market.get_ata(market.collateral.collateralMint));
+
+    assert.equal(finalQuoteVault.amount, initialQuoteVault.amount);
+    assert.equal(finalCollateralVault.amount, initialCollateralVault.amount);
+    assert.equal(await bob.get_quo_balance(), BigInt(0));
+  });
+
+  it("fails without a matching flash repay", async () => {
+    await assert.rejects(
+      async () => {
+        await market.flashLoan({
+          user: bob,
+          quoteAmount: new anchor.BN(1 * 1e9),
+          collateralAmount: new anchor.BN(0),
+          repay: false,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Flash loan was not repaid");
+        return true;
+      }
+    );
+  });
+
+  it("fails for a zero amount", async () => {
+    await assert.rejects(
+      async () => {
+        await market.flashLoan({
+          user: bob,
+          quoteAmount: new anchor.BN(0),
+          collateralAmount: new anchor.BN(0),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid input");
+        return true;
+      }
+    );
+  });
+
+  it("fails while borrows or collateral withdrawals are paused", async () => {
+    await market.setMarketPause({
+      user: futarchy,
+      borrow: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.flashLoan({
+          user: bob,
+          quoteAmount: new anchor.BN(1 * 1e9),
+          collateralAmount: new anchor.BN(0),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+
+    // collateral can still be lent while only borrows are paused
+    await market.flashLoan({
+      user: bob,
+      quoteAmount: new anchor.BN(0),
+      collateralAmount: new anchor.BN(1 * 1e9),
+    });
+
+    await market.setMarketPause({
+      user: futarchy,
+      withdrawCollateral: true,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.flashLoan({
+          user: bob,
+          quoteAmount: new anchor.BN(0),
+          collateralAmount: new anchor.BN(1 * 1e9),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+  });
+});
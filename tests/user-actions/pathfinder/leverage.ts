@@ -0,0 +1,170 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+const MOCK_SWAP_IDL = require("../../../target/idl/mock_swap.json");
+const MOCK_SWAP_PROGRAM_ID = new anchor.web3.PublicKey(MOCK_SWAP_IDL.address);
+
+describe("Leverage", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let trader: UserFixture;
+  let lender: UserFixture;
+  let pool: UserFixture;  // AMM selling collateral for the borrowed quote
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    lender = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    trader = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(100 * 1e9)
+    );
+
+    pool = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1000 * 1e9)
+    );
+
+    let futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(8 * 1e8), // 80% LTV
+      price: new anchor.BN(1e5), // $1.00
+      conf: new anchor.BN(1 * 10 ** 4), // $0.01 confidence interval
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: lender });
+
+    await market.deposit({
+      user: lender,
+      amount: new anchor.BN(1000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: lender,
+    });
+  });
+
+  // pool pays `collateralOut` for the quote borrowed by the trader
+  function swap(collateralOut: anchor.BN) {
+    return {
+      data: collateralOut.toArrayLike(Buffer, "le", 8),
+      callback: {
+        programId: MOCK_SWAP_PROGRAM_ID,
+        accounts: [
+          { pubkey: trader.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: pool.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: trader.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: trader.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: pool.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: pool.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: anchor.utils.token.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        signers: [pool.key.payer],
+      },
+    };
+  }
+
+  it("opens a levered position in one pass", async () => {
+    // 100 own collateral and 150 bought with borrowed quote, ~61% ltv
+    await market.leverage({
+      user: trader,
+      collateralAmount: new anchor.BN(100 * 1e9),
+      quoteAmount: new anchor.BN(150 * 1e9),
+      minCollateralOut: new anchor.BN(149 * 1e9),
+      maxLtv: new anchor.BN(7 * 1e8),
+      ...swap(new anchor.BN(150 * 1e9)),
+    });
+
+    const shares = await market
+      .get_borrower_shares(trader.key.publicKey)
+      .get_data();
+    assert.equal(shares.collateralAmount.toString(), (250 * 1e9).toString());
+    assert.ok(shares.borrowShares.gtn(0), "Debt should be recorded");
+
+    const marketData = await market.marketAcc.get_data();
+    assert.equal(marketData.totalCollateral.toString(), (250 * 1e9).toString());
+    const totalBorrows = await market.marketAcc.getTotalBorrows();
+    assert.ok(
+      totalBorrows.sub(new anchor.BN(150 * 1e9)).abs().lten(1),
+      "Debt should match the borrowed quote"
+    );
+
+    // everything borrowed went to the pool
+    assert.equal(await trader.get_quo_balance(), BigInt(0));
+    assert.equal(await trader.get_col_balance(), BigInt(0));
+    assert.equal(await pool.get_quo_balance(), BigInt(150 * 1e9));
+  });
+
+  it("fails below the minimum collateral out", async () => {
+    await assert.rejects(
+      async () => {
+        await market.leverage({
+          user: trader,
+          collateralAmount: new anchor.BN(100 * 1e9),
+          quoteAmount: new anchor.BN(150 * 1e9),
+          minCollateralOut: new anchor.BN(151 * 1e9),
+          maxLtv: new anchor.BN(7 * 1e8),
+          ...swap(new anchor.BN(150 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Swap returned less than the minimum output");
+        return true;
+      }
+    );
+  });
+
+  it("fails past the requested max ltv", async () => {
+    await assert.rejects(
+      async () => {
+        await market.leverage({
+          user: trader,
+          collateralAmount: new anchor.BN(100 * 1e9),
+          quoteAmount: new anchor.BN(150 * 1e9),
+          minCollateralOut: new anchor.BN(0),
+          maxLtv: new anchor.BN(6 * 1e8),
+          ...swap(new anchor.BN(150 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Position ltv above the requested max");
+        return true;
+      }
+    );
+  });
+
+  it("fails if the position ends insolvent", async () => {
+    // a bad fill leaves 400 of debt against 400 collateral
+    await assert.rejects(
+      async () => {
+        await market.leverage({
+          user: trader,
+          collateralAmount: new anchor.BN(100 * 1e9),
+          quoteAmount: new anchor.BN(400 * 1e9),
+          minCollateralOut: new anchor.BN(0),
+          maxLtv: new anchor.BN(1e9),
+          ...swap(new anchor.BN(300 * 1e9)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+  });
+});
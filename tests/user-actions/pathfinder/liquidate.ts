@@ -3,12 +3,16 @@ import { MarketFixture, UserFixture } from "../../fixtures";
 import * as anchor from "@coral-xyz/anchor";
 import assert from "assert";
 
+const MOCK_SWAP_IDL = require("../../../target/idl/mock_swap.json");
+const MOCK_SWAP_PROGRAM_ID = new anchor.web3.PublicKey(MOCK_SWAP_IDL.address);
+
 describe("Liquidate", () => {
   let test: TestUtils;
   let market: MarketFixture;
   let liquidator: UserFixture;  // User performing the liquidation
   let borrower: UserFixture;    // User being liquidated
   let lender: UserFixture;      // User providing liquidity
+  let futarchy: UserFixture;    // Market authority
 
   beforeEach(async () => {
     test = await TestUtils.create({
@@ -34,14 +38,14 @@ describe("Liquidate", () => {
       new anchor.BN(0)
     );
 
-    let futarchy = await test.createUser(
+    futarchy = await test.createUser(
       new anchor.BN(0),
       new anchor.BN(0)
     );
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(8 * 1e8), // 80% LTV
+      maxBorrowLtv: new anchor.BN(8 * 1e8), // 80% LTV
       price: new anchor.BN(1e5), // $1.00
       conf: new anchor.BN(1 * 10 ** 4), // $0.01 confidence interval
       expo: -5,
@@ -124,6 +128,236 @@ describe("Liquidate", () => {
     );
   });
 
+  it("socializes bad debt among lenders", async () => {
+    // Update price so the collateral no longer covers the debt
+    await market.collateral.setPrice({
+      price: new anchor.BN(1 * 1e4),  // $0.10
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    const initialDeposits = await market.marketAcc.getTotalDeposits();
+
+    // Seize all of the borrower's collateral
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(100 * 1e9),
+      repayShares: new anchor.BN(0)
+    });
+
+    const borrowerShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(borrowerShares.collateralAmount.toNumber(), 0);
+    assert.equal(borrowerShares.borrowShares.toNumber(), 0);
+
+    const marketData = await market.marketAcc.get_data();
+    assert.equal(marketData.totalBorrowShares.toNumber(), 0);
+    assert.ok(marketData.badDebt.gtn(0), "Bad debt should be recorded");
+
+    // Lenders absorb the loss through the deposit index
+    const finalDeposits = await market.marketAcc.getTotalDeposits();
+    assert.ok(
+      initialDeposits.sub(finalDeposits).sub(marketData.badDebt).abs().lten(1),
+      "Deposits should be written down by the bad debt"
+    );
+  });
+
+  it("pauses the market when bad debt wipes out every deposit", async () => {
+    // a market of its own, lent out entirely against a single unit of collateral
+    const wipeoutTest = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    const wipeoutLender = await wipeoutTest.createUser(
+      new anchor.BN("2000000000000000000"),
+      new anchor.BN(0)
+    );
+    const wipeoutBorrower = await wipeoutTest.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1)
+    );
+    const wipeoutLiquidator = await wipeoutTest.createUser(
+      new anchor.BN(1 * 1e9),
+      new anchor.BN(0)
+    );
+    const wipeoutFutarchy = await wipeoutTest.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    const wipeoutMarket = await wipeoutTest.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(8 * 1e8),
+      price: new anchor.BN("3000000000000000000"),
+      conf: new anchor.BN(0),
+      expo: 0,
+      feeRecipient: wipeoutFutarchy,
+      authority: wipeoutFutarchy,
+    });
+
+    await wipeoutMarket.createAndSetAuthority({ user: wipeoutLender });
+
+    await wipeoutMarket.deposit({
+      user: wipeoutLender,
+      amount: new anchor.BN("2000000000000000000"),
+      shares: new anchor.BN(0),
+      owner: wipeoutLender,
+    });
+
+    await wipeoutMarket.depositCollateral({
+      user: wipeoutBorrower,
+      amount: new anchor.BN(1),
+      owner: wipeoutBorrower,
+    });
+
+    await wipeoutMarket.borrow({
+      user: wipeoutBorrower,
+      amount: new anchor.BN("2000000000000000000"),
+      shares: new anchor.BN(0),
+      owner: wipeoutBorrower,
+      recipient: wipeoutBorrower,
+    });
+
+    // the collateral is now worth a single quote base unit
+    await wipeoutMarket.collateral.setPrice({
+      price: new anchor.BN(1),
+      conf: new anchor.BN(0),
+    });
+
+    await wipeoutMarket.liquidate({
+      user: wipeoutLiquidator,
+      borrower: wipeoutBorrower.key.publicKey,
+      collateralAmount: new anchor.BN(1),
+      repayShares: new anchor.BN(0),
+    });
+
+    const marketData = await wipeoutMarket.marketAcc.get_data();
+    assert.equal(marketData.depositIndex.toString(), "0");
+    assert.equal(marketData.paused.deposit, true);
+    assert.equal(marketData.paused.borrow, true);
+
+    // new lenders cannot pour funds into the written off index
+    await assert.rejects(
+      async () => {
+        await wipeoutMarket.deposit({
+          user: wipeoutLiquidator,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: wipeoutLiquidator,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market action is paused");
+        return true;
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await wipeoutMarket.setMarketPause({
+          user: wipeoutFutarchy,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Market deposits were wiped out by bad debt");
+        return true;
+      }
+    );
+  });
+
+  it("liquidates through a swap callback without quote inventory", async () => {
+    // Update price to make position underwater (50% price drop)
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    // Pool buying the seized collateral for quote tokens
+    let pool = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    // Liquidator without any quote tokens
+    let swapper = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    await market.liquidate({
+      user: swapper,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(2 * 1e9),
+      repayShares: new anchor.BN(0),
+      callback: {
+        programId: MOCK_SWAP_PROGRAM_ID,
+        accounts: [
+          { pubkey: swapper.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: pool.key.publicKey, isSigner: true, isWritable: false },
+          { pubkey: swapper.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: swapper.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: pool.collateralAta, isSigner: false, isWritable: true },
+          { pubkey: pool.quoteAta, isSigner: false, isWritable: true },
+          { pubkey: anchor.utils.token.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        signers: [pool.key.payer],
+      },
+    });
+
+    assert.equal(await swapper.get_quo_balance(), BigInt(0));
+    assert.equal(await swapper.get_col_balance(), BigInt(0));
+    assert.equal(await pool.get_col_balance(), BigInt(2_000_000_000));
+    assert.equal(
+      BigInt(1000 * 1e9) - (await pool.get_quo_balance() as bigint),
+      BigInt(1_043_478_261),
+      "Incorrect quote paid by the pool"
+    );
+  });
+
+  it("fails if the callback does not return enough quote", async () => {
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    // Pool without enough quote to cover the repayment
+    let pool = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    let swapper = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: swapper,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(2 * 1e9),
+          repayShares: new anchor.BN(0),
+          callback: {
+            programId: MOCK_SWAP_PROGRAM_ID,
+            accounts: [
+              { pubkey: swapper.key.publicKey, isSigner: true, isWritable: false },
+              { pubkey: pool.key.publicKey, isSigner: true, isWritable: false },
+              { pubkey: swapper.collateralAta, isSigner: false, isWritable: true },
+              { pubkey: swapper.quoteAta, isSigner: false, isWritable: true },
+              { pubkey: pool.collateralAta, isSigner: false, isWritable: true },
+              { pubkey: pool.quoteAta, isSigner: false, isWritable: true },
+              { pubkey: anchor.utils.token.TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            ],
+            signers: [pool.key.payer],
+          },
+        });
+      }
+    );
+  });
+
   it("fails if borrower is solvent", async () => {
     await assert.rejects(
       async () => {
@@ -141,6 +375,51 @@ describe("Liquidate", () => {
     );
   });
 
+  it("fails between the max borrow ltv and the liquidation ltv", async () => {
+    // $0.84 low end puts the position at ~83% ltv, past 80% but within 85%
+    await market.collateral.setPrice({
+      price: new anchor.BN(85 * 1e3),
+      conf: new anchor.BN(1 * 10 ** 3),
+    });
+
+    const [borrowHealth, liquidationHealth] = await market.viewPositionHealth(
+      borrower.key.publicKey
+    );
+    assert.equal(borrowHealth.toString(), "960000000000000000");
+    assert.equal(liquidationHealth.toString(), "1020000000000000000");
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: borrower,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: borrower,
+          recipient: borrower,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: liquidator,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(1 * 1e9),
+          repayShares: new anchor.BN(0)
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Borrower is solvent");
+        return true;
+      }
+    );
+  });
+
   it("fails if liquidator lacks sufficient quote tokens", async () => {
     // Update price to make position underwater (50% price drop)
     await market.collateral.setPrice({
@@ -169,5 +448,343 @@ describe("Liquidate", () => {
       }
     );
   });
-});
 
+  it("fails an auction liquidation before the auction starts", async () => {
+    await market.updateLiquidationAuction({
+      user: futarchy,
+      duration: new anchor.BN(1000),
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: liquidator,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(2 * 1e9),
+          repayShares: new anchor.BN(0)
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Liquidation auction has not started");
+        return true;
+      }
+    );
+  });
+
+  it("ramps the auction incentive up to the cap", async () => {
+    await market.updateLiquidationAuction({
+      user: futarchy,
+      duration: new anchor.BN(1000),
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await market.updateLiquidationState({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+    });
+
+    const state = await market
+      .get_liquidation_state(borrower.key.publicKey)
+      .get_data();
+    assert.equal(state.insolventSince.toNumber(), await test.getTime());
+
+    // halfway through the auction the incentive is half of the static one
+    await test.moveTimeForward(500);
+
+    const initialLiquidatorQuote = await liquidator.get_quo_balance();
+
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(2 * 1e9),
+      repayShares: new anchor.BN(0),
+      auction: true,
+    });
+
+    // 2 collateral at the $0.60 high price, divided by a ~1.075 incentive
+    const spent = initialLiquidatorQuote - (await liquidator.get_quo_balance());
+    assert.ok(
+      spent > BigInt(1_116_000_000) && spent < BigInt(1_117_000_000),
+      "Liquidator should repay more than with the static incentive"
+    );
+
+    // a recovered position clears the auction
+    await market.collateral.setPrice({
+      price: new anchor.BN(1e5),  // $1.00
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await market.updateLiquidationState({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+    });
+
+    const cleared = await market
+      .get_liquidation_state(borrower.key.publicKey)
+      .get_data();
+    assert.equal(cleared.insolventSince.toNumber(), 0);
+  });
+
+  it("restarts the auction after the borrower cures the position", async () => {
+    await market.updateLiquidationAuction({
+      user: futarchy,
+      duration: new anchor.BN(1000),
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await market.updateLiquidationState({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+    });
+
+    // the borrower cures the position while nobody updates the record
+    await market.collateral.setPrice({
+      price: new anchor.BN(1e5),  // $1.00
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await market.repay({
+      user: borrower,
+      owner: borrower,
+      amount: new anchor.BN(10 * 1e9),
+      shares: new anchor.BN(0)
+    });
+
+    await test.moveTimeForward(1000);
+
+    // insolvent again, the old record must not pay the full incentive
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: liquidator,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(2 * 1e9),
+          repayShares: new anchor.BN(0),
+          auction: true,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Liquidation auction has not started");
+        return true;
+      }
+    );
+
+    await market.updateLiquidationState({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+    });
+
+    const state = await market
+      .get_liquidation_state(borrower.key.publicKey)
+      .get_data();
+    assert.equal(state.insolventSince.toNumber(), await test.getTime());
+  });
+
+  it("rejects an auction duration over the max", async () => {
+    await assert.rejects(
+      async () => {
+        await market.updateLiquidationAuction({
+          user: futarchy,
+          duration: new anchor.BN(2 * 24 * 3600),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid liquidation auction duration");
+        return true;
+      }
+    );
+  });
+
+  it("caps a liquidation at the close factor", async () => {
+    // health ~0.6, above the full close threshold
+    await market.updateLiquidationLimits({
+      user: futarchy,
+      limits: {
+        closeFactor: new anchor.BN("500000000000000000"),
+        fullCloseHealth: new anchor.BN("500000000000000000"),
+        minRemainingDebt: new anchor.BN(0),
+      },
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    const { borrowShares } = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: liquidator,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(0),
+          repayShares: borrowShares.muln(6).divn(10),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Liquidation exceeds the close factor or leaves dust debt");
+        return true;
+      }
+    );
+
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(0),
+      repayShares: borrowShares.divn(2),
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(
+      finalShares.borrowShares.toString(),
+      borrowShares.sub(borrowShares.divn(2)).toString()
+    );
+  });
+
+  it("lifts the close factor below the full close health", async () => {
+    await market.updateLiquidationLimits({
+      user: futarchy,
+      limits: {
+        closeFactor: new anchor.BN("500000000000000000"),
+        fullCloseHealth: new anchor.BN("900000000000000000"),
+        minRemainingDebt: new anchor.BN(0),
+      },
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    const { borrowShares } = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(0),
+      repayShares: borrowShares.muln(6).divn(10),
+    });
+  });
+
+  it("forces a full liquidation instead of leaving dust debt", async () => {
+    await market.updateLiquidationLimits({
+      user: futarchy,
+      limits: {
+        closeFactor: new anchor.BN(0),
+        fullCloseHealth: new anchor.BN(0),
+        minRemainingDebt: new anchor.BN(10 * 1e9),
+      },
+    });
+
+    // ~87% ltv, with enough collateral to close the position at the high price
+    await market.collateral.setPrice({
+      price: new anchor.BN(81 * 1e3),
+      conf: new anchor.BN(5 * 10 ** 2),
+    });
+
+    const { borrowShares } = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+
+    // leaves about 5 quote tokens of debt
+    await assert.rejects(
+      async () => {
+        await market.liquidate({
+          user: liquidator,
+          borrower: borrower.key.publicKey,
+          collateralAmount: new anchor.BN(0),
+          repayShares: borrowShares.sub(borrowShares.divn(14)),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Liquidation exceeds the close factor or leaves dust debt");
+        return true;
+      }
+    );
+
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(0),
+      repayShares: borrowShares,
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(finalShares.borrowShares.toNumber(), 0);
+    assert.ok(finalShares.collateralAmount.gtn(0), "Borrower keeps the surplus collateral");
+  });
+
+  it("pays the protocol cut of the incentive to the fee recipient", async () => {
+    // half of the 15% incentive
+    await market.updateLiquidationFee({
+      user: futarchy,
+      liquidationFeeFactor: new anchor.BN("500000000000000000"),
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(5 * 1e4),  // $0.50
+      conf: new anchor.BN(1 * 10 ** 4),
+    });
+
+    const initialLiquidatorQuote = await liquidator.get_quo_balance();
+
+    await market.liquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(2 * 1e9),
+      repayShares: new anchor.BN(0)
+    });
+
+    // repaid debt plus 1_043_478_261 * 0.15 * 0.5
+    assert.equal(
+      initialLiquidatorQuote - (await liquidator.get_quo_balance()),
+      BigInt(1_043_478_261 + 78_260_869),
+      "Incorrect quote token change"
+    );
+
+    const marketData = await market.marketAcc.get_data();
+    assert.ok(marketData.feeShares.gtn(0), "Fee shares should be minted");
+  });
+
+  it("rejects a liquidation fee over the max", async () => {
+    await assert.rejects(
+      async () => {
+        await market.updateLiquidationFee({
+          user: futarchy,
+          liquidationFeeFactor: new anchor.BN("600000000000000000"),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Fee factor exceeds max");
+        return true;
+      }
+    );
+  });
+});
@@ -0,0 +1,152 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+// whole tokens in base units
+function units(amount: number, decimals: number): anchor.BN {
+  return new anchor.BN(amount).mul(new anchor.BN(10).pow(new anchor.BN(decimals)));
+}
+
+// tenths of a whole quote token per collateral token, scaled by the oracle exponent
+function price(tenths: number, expo: number): anchor.BN {
+  return new anchor.BN(tenths).mul(new anchor.BN(10).pow(new anchor.BN(-expo - 1)));
+}
+
+const CASES = [
+  { collateralDecimals: 9, quoteDecimals: 6, expo: -8 },
+  { collateralDecimals: 6, quoteDecimals: 9, expo: -5 },
+  { collateralDecimals: 18, quoteDecimals: 8, expo: -6 },
+  { collateralDecimals: 5, quoteDecimals: 5, expo: -10 },
+];
+
+describe("Mixed Decimals", () => {
+  for (const { collateralDecimals, quoteDecimals, expo } of CASES) {
+    describe(`${collateralDecimals} decimal collateral, ${quoteDecimals} decimal quote, exponent ${expo}`, () => {
+      let test: TestUtils;
+      let market: MarketFixture;
+      let larry: UserFixture;
+      let bob: UserFixture;
+      let liquidator: UserFixture;
+
+      beforeEach(async () => {
+        test = await TestUtils.create({
+          quoteDecimals,
+          collateralDecimals,
+        });
+
+        larry = await test.createUser(
+          units(1_000, quoteDecimals),
+          new anchor.BN(0)
+        );
+
+        bob = await test.createUser(
+          new anchor.BN(0),
+          units(10, collateralDecimals)
+        );
+
+        liquidator = await test.createUser(
+          units(100, quoteDecimals),
+          new anchor.BN(0)
+        );
+
+        let futarchy = await test.createUser(
+          new anchor.BN(0),
+          new anchor.BN(0)
+        );
+
+        // $2.50 collateral, 10 tokens support 20 quote tokens at 80%
+        market = await test.createMarket({
+          symbol: "BONK",
+          maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+          price: price(25, expo),
+          conf: new anchor.BN(0),
+          expo,
+          feeRecipient: futarchy,
+          authority: futarchy,
+        });
+
+        await market.createAndSetAuthority({ user: larry });
+
+        await market.deposit({
+          user: larry,
+          amount: units(1_000, quoteDecimals),
+          shares: new anchor.BN(0),
+          owner: larry,
+        });
+
+        await market.depositCollateral({
+          user: bob,
+          amount: units(10, collateralDecimals),
+          owner: bob,
+        });
+      });
+
+      it("borrows up to the max ltv in whole tokens", async () => {
+        await market.borrow({
+          user: bob,
+          amount: units(20, quoteDecimals),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+
+        assert.equal(
+          await bob.get_quo_balance(),
+          BigInt(units(20, quoteDecimals).toString())
+        );
+
+        await assert.rejects(
+          async () => {
+            await market.borrow({
+              user: bob,
+              amount: new anchor.BN(1),
+              shares: new anchor.BN(0),
+              owner: bob,
+              recipient: bob,
+            });
+          },
+          (err: anchor.AnchorError) => {
+            assert.strictEqual(err.error.errorMessage, "User is not solvent");
+            return true;
+          }
+        );
+      });
+
+      it("prices seized collateral in whole tokens", async () => {
+        await market.borrow({
+          user: bob,
+          amount: units(20, quoteDecimals),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+
+        // $2.00, the position is now at 100% ltv
+        await market.collateral.setPrice({
+          price: price(20, expo),
+          conf: new anchor.BN(0),
+        });
+
+        const initialQuote = await liquidator.get_quo_balance();
+
+        await market.liquidate({
+          user: liquidator,
+          borrower: bob.key.publicKey,
+          collateralAmount: units(1, collateralDecimals),
+          repayShares: new anchor.BN(0),
+        });
+
+        // one collateral token is worth 2 quote tokens, less the liquidation incentive
+        const spent = initialQuote - (await liquidator.get_quo_balance());
+        assert.ok(spent > BigInt(units(19, quoteDecimals).toString()) / BigInt(10));
+        assert.ok(spent < BigInt(units(2, quoteDecimals).toString()));
+
+        assert.equal(
+          await liquidator.get_col_balance(),
+          BigInt(units(1, collateralDecimals).toString())
+        );
+      });
+    });
+  }
+});
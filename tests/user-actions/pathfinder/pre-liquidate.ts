@@ -0,0 +1,213 @@
+import { TestUtils } from "../../utils";
+import { MarketFixture, UserFixture } from "../../fixtures";
+import * as anchor from "@coral-xyz/anchor";
+import assert from "assert";
+
+const WAD = new anchor.BN("1000000000000000000");
+
+// factors scaled by 1e18, pre lltv by 1e9
+const PRE_LIQUIDATION = {
+  preLltv: new anchor.BN(8 * 1e8),
+  preLcf1: WAD.divn(10),
+  preLcf2: WAD.divn(2),
+  preLif1: new anchor.BN("1010000000000000000"),
+  preLif2: new anchor.BN("1050000000000000000"),
+};
+
+describe("Pre-liquidate", () => {
+  let test: TestUtils;
+  let market: MarketFixture;
+  let liquidator: UserFixture;
+  let borrower: UserFixture;
+  let lender: UserFixture;
+  let futarchy: UserFixture;
+
+  beforeEach(async () => {
+    test = await TestUtils.create({
+      quoteDecimals: 9,
+      collateralDecimals: 9,
+    });
+
+    lender = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    borrower = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(1000 * 1e9)
+    );
+
+    liquidator = await test.createUser(
+      new anchor.BN(1000 * 1e9),
+      new anchor.BN(0)
+    );
+
+    futarchy = await test.createUser(
+      new anchor.BN(0),
+      new anchor.BN(0)
+    );
+
+    // 80% max borrow ltv, 85% liquidation ltv
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(8 * 1e8),
+      price: new anchor.BN(1e5),
+      conf: new anchor.BN(1 * 10 ** 4),
+      expo: -5,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: lender });
+
+    await market.deposit({
+      user: lender,
+      amount: new anchor.BN(1000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: lender,
+    });
+
+    await market.depositCollateral({
+      user: borrower,
+      amount: new anchor.BN(100 * 1e9),
+      owner: borrower
+    });
+
+    await market.borrow({
+      user: borrower,
+      amount: new anchor.BN(70 * 1e9),
+      shares: new anchor.BN(0),
+      owner: borrower,
+      recipient: borrower,
+    });
+  });
+
+  async function enterBand() {
+    // $0.84 low end puts the position at ~83% ltv
+    await market.collateral.setPrice({
+      price: new anchor.BN(85 * 1e3),
+      conf: new anchor.BN(1 * 10 ** 3),
+    });
+  }
+
+  async function expectError(fn: () => Promise<void>, message: string) {
+    await assert.rejects(fn, (err: anchor.AnchorError) => {
+      assert.strictEqual(err.error.errorMessage, message);
+      return true;
+    });
+  }
+
+  it("pre-liquidates a position in the band", async () => {
+    await market.updatePreLiquidation({ user: futarchy, preLiquidation: PRE_LIQUIDATION });
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: true });
+    await enterBand();
+
+    const initialShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    const initialQuote = await liquidator.get_quo_balance();
+    const initialCollateral = await liquidator.get_col_balance();
+
+    const repayShares = initialShares.borrowShares.divn(10);
+    await market.preLiquidate({
+      user: liquidator,
+      borrower: borrower.key.publicKey,
+      collateralAmount: new anchor.BN(0),
+      repayShares,
+    });
+
+    const finalShares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+    assert.equal(
+      finalShares.borrowShares.toString(),
+      initialShares.borrowShares.sub(repayShares).toString()
+    );
+
+    const spent = initialQuote - (await liquidator.get_quo_balance());
+    const seized = (await liquidator.get_col_balance()) - initialCollateral;
+    assert.ok(spent > BigInt(0), "Liquidator should repay debt");
+    assert.equal(
+      initialShares.collateralAmount.sub(finalShares.collateralAmount).toString(),
+      seized.toString()
+    );
+    // seized collateral at the high price is worth more than the repaid debt
+    assert.ok(seized * BigInt(86) > spent * BigInt(100), "Incentive should be paid");
+  });
+
+  it("fails when pre-liquidation is disabled", async () => {
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: true });
+    await enterBand();
+
+    await expectError(async () => {
+      await market.preLiquidate({
+        user: liquidator,
+        borrower: borrower.key.publicKey,
+        collateralAmount: new anchor.BN(1 * 1e9),
+        repayShares: new anchor.BN(0),
+      });
+    }, "Pre-liquidation is disabled for this market");
+  });
+
+  it("fails when the borrower has revoked authorization", async () => {
+    await market.updatePreLiquidation({ user: futarchy, preLiquidation: PRE_LIQUIDATION });
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: true });
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: false });
+    await enterBand();
+
+    await expectError(async () => {
+      await market.preLiquidate({
+        user: liquidator,
+        borrower: borrower.key.publicKey,
+        collateralAmount: new anchor.BN(1 * 1e9),
+        repayShares: new anchor.BN(0),
+      });
+    }, "Borrower has not authorized pre-liquidations");
+  });
+
+  it("fails outside the pre-liquidation band", async () => {
+    await market.updatePreLiquidation({ user: futarchy, preLiquidation: PRE_LIQUIDATION });
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: true });
+
+    await expectError(async () => {
+      await market.preLiquidate({
+        user: liquidator,
+        borrower: borrower.key.publicKey,
+        collateralAmount: new anchor.BN(1 * 1e9),
+        repayShares: new anchor.BN(0),
+      });
+    }, "Position is not in the pre-liquidation band");
+  });
+
+  it("fails past the close factor", async () => {
+    await market.updatePreLiquidation({ user: futarchy, preLiquidation: PRE_LIQUIDATION });
+    await market.setPreLiquidationAuthorization({ user: borrower, enabled: true });
+    await enterBand();
+
+    const shares = await market
+      .get_borrower_shares(borrower.key.publicKey)
+      .get_data();
+
+    await expectError(async () => {
+      await market.preLiquidate({
+        user: liquidator,
+        borrower: borrower.key.publicKey,
+        collateralAmount: new anchor.BN(0),
+        repayShares: shares.borrowShares.divn(2),
+      });
+    }, "Pre-liquidation close factor exceeded");
+  });
+
+  it("rejects an incentive above the liquidation ltv allows", async () => {
+    await expectError(async () => {
+      await market.updatePreLiquidation({
+        user: futarchy,
+        preLiquidation: {
+          ...PRE_LIQUIDATION,
+          preLif2: new anchor.BN("1200000000000000000"),
+        },
+      });
+    }, "Invalid pre-liquidation config");
+  });
+});
@@ -16,12 +16,12 @@ describe("Repay", () => {
     });
 
     larry = await test.createUser(
-      new anchor.BN(1100 * 1e9),
+      new anchor.BN(1100 * 1e5),
       new anchor.BN(0)
     );
 
     bob = await test.createUser(
-      new anchor.BN(100 * 1e9),
+      new anchor.BN(100 * 1e5),
       new anchor.BN(1000 * 1e9)
     );
 
@@ -32,7 +32,7 @@ describe("Repay", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 10 ** 5),
       conf: new anchor.BN(10 * 1e5),
       expo: -5,
@@ -44,7 +44,7 @@ describe("Repay", () => {
 
     await market.deposit({
       user: larry,
-      amount: new anchor.BN(1000 * 1e9),
+      amount: new anchor.BN(1000 * 1e5),
       shares: new anchor.BN(0),
       owner: larry,
     });
@@ -61,7 +61,7 @@ describe("Repay", () => {
       user: bob,
       owner: bob,
       recipient: bob,
-      amount: new anchor.BN(50 * 1e9),
+      amount: new anchor.BN(50 * 1e5),
       shares: new anchor.BN(0)
     });
   });
@@ -80,7 +80,7 @@ describe("Repay", () => {
     await market.repay({
       user: bob,
       owner: bob,
-      amount: new anchor.BN(50 * 1e9),
+      amount: new anchor.BN(50 * 1e5),
       shares: new anchor.BN(0)
     });
 
@@ -95,14 +95,14 @@ describe("Repay", () => {
     // Verify quote token balance decreased by repayment amount
     assert.equal(
       initialQuoteBalance - finalQuoteBalance,
-      BigInt(50 * 1e9),
+      BigInt(50 * 1e5),
       "Quote balance should decrease by 50 tokens"
     );
 
     // Verify market total borrow assets decreased
     assert.ok(
       finalBorrows.eq(
-        initialBorrows.sub(new anchor.BN(50 * 1e9))
+        initialBorrows.sub(new anchor.BN(50 * 1e5))
       ),
       "Market total borrow assets should decrease by 50"
     );
@@ -122,7 +122,7 @@ describe("Repay", () => {
     // Verify bob's final quote balance is correct
     assert.equal(
       finalQuoteBalance,
-      BigInt(100 * 1e9), // Started with 0, borrowed 50, repaid 50
+      BigInt(100 * 1e5), // Started with 0, borrowed 50, repaid 50
       "Bob's final quote balance should be 950 tokens"
     );
   });
@@ -136,7 +136,7 @@ describe("Repay", () => {
       .get_data();
 
     // Instead of converting to number, compare BNs directly -> 0.05 * 1e18
-    assert.equal(initialBorrowerShares.borrowShares.toNumber(), 50 * 1e9);
+    assert.equal(initialBorrowerShares.borrowShares.toNumber(), 50 * 1e5);
 
     // Move time forward one day
     await test.moveTimeForward(1 * 24 * 60 * 60);
@@ -149,7 +149,7 @@ describe("Repay", () => {
       user: bob,
       owner: bob,
       amount: new anchor.BN(0),
-      shares: new anchor.BN(50 * 1e9)
+      shares: new anchor.BN(50 * 1e5)
     });
 
     // Get final state
@@ -164,7 +164,7 @@ describe("Repay", () => {
     // Verify quote token balance decreased by repayment amount
     assert.equal(
       initialQuoteBalance - finalQuoteBalance,
-      new anchor.BN(50.001598199 * 1e9),
+      new anchor.BN(5_000_159),
       "Quote balance should decrease by 50 tokens"
     );
 
@@ -172,7 +172,7 @@ describe("Repay", () => {
     assert.equal(finalBorrows.toNumber(), new anchor.BN(0));
 
     // Verify market total deposits has increased
-    assert.equal(finalDeposits.toNumber(), new anchor.BN(1000.031963981 * 1e9));
+    assert.equal(finalDeposits.toNumber(), new anchor.BN(100_003_196));
 
     // Verify market total borrow shares decreased
     assert.ok(
@@ -189,7 +189,7 @@ describe("Repay", () => {
     // Verify bob's final quote balance is correct
     assert.equal(
       finalQuoteBalance,
-      BigInt(99.998401801 * 1e9), // Started with 0, borrowed 50, repaid 50
+      BigInt(9_999_841), // Started with 0, borrowed 50, repaid 50
       "Bob's final quote balance should be 950 tokens"
     );
   });
@@ -201,7 +201,7 @@ describe("Repay", () => {
       user: bob,
       owner: bob,
       recipient: bob,
-      amount: new anchor.BN(50 * 1e9), // Borrow additional 50 tokens
+      amount: new anchor.BN(50 * 1e5), // Borrow additional 50 tokens
       shares: new anchor.BN(0)
     });
 
@@ -216,7 +216,7 @@ describe("Repay", () => {
     await market.repay({
       user: bob,
       owner: bob,
-      amount: new anchor.BN(25 * 1e9), // Repay 25 tokens
+      amount: new anchor.BN(25 * 1e5), // Repay 25 tokens
       shares: new anchor.BN(0)
     });
 
@@ -232,20 +232,20 @@ describe("Repay", () => {
     // Verify quote token balance decreased by repayment amount
     assert.equal(
       initialQuoteBalance - finalQuoteBalance,
-      BigInt(25 * 1e9),
+      BigInt(25 * 1e5),
       "Quote balance should decrease by 25 tokens"
     );
 
     // Verify market total borrow assets decreased by repayment amount
     assert.equal(
       finalBorrows.toNumber(),
-      initialBorrows.sub(new anchor.BN(25 * 1e9)).toNumber()
+      initialBorrows.sub(new anchor.BN(25 * 1e5)).toNumber()
     );
 
     // Verify bob's final quote balance is correct
     assert.equal(
       finalQuoteBalance,
-      new anchor.BN(175 * 1e9), // Started with 50, borrowed 50, repaid 25
+      new anchor.BN(175 * 1e5), // Started with 50, borrowed 50, repaid 25
       "Bob's final quote balance should be 25 tokens"
     );
   });
@@ -256,7 +256,7 @@ describe("Repay", () => {
     await market.repay({
       user: bob,
       owner: bob,
-      amount: new anchor.BN(50 * 1e9),
+      amount: new anchor.BN(50 * 1e5),
       shares: new anchor.BN(0)
     });
 
@@ -277,7 +277,7 @@ describe("Repay", () => {
           user: bob,
           owner: bob,
           amount: new anchor.BN(0),
-          shares: new anchor.BN(100 * 1e9)
+          shares: new anchor.BN(100 * 1e5)
         });
       },
       (err: anchor.AnchorError) => {
@@ -294,8 +294,8 @@ describe("Repay", () => {
     const initialMarketData = await market.marketAcc.get_data();
     const initialBorrows = await market.marketAcc.getTotalBorrows();
 
-    assert.equal(priorBobQuoteBalance, BigInt(150 * 1e9));
-    assert.equal(priorLarryQuoteBalance, BigInt(100 * 1e9));
+    assert.equal(priorBobQuoteBalance, BigInt(150 * 1e5));
+    assert.equal(priorLarryQuoteBalance, BigInt(100 * 1e5));
 
     const priorBobBorrowerShares = await market
       .get_borrower_shares(bob.key.publicKey)
@@ -305,7 +305,7 @@ describe("Repay", () => {
     await market.repay({
       user: larry,
       owner: bob,
-      amount: new anchor.BN(50 * 1e9),
+      amount: new anchor.BN(50 * 1e5),
       shares: new anchor.BN(0)
     });
 
@@ -331,14 +331,14 @@ describe("Repay", () => {
 
     assert.equal(
       priorLarryQuoteBalance - finalLarryQuoteBalance,
-      BigInt(50 * 1e9),
+      BigInt(50 * 1e5),
       "Larry's quote balance should decrease by 50 tokens"
     );
 
     // Verify market total borrow assets decreased
     assert.ok(
       finalBorrows.eq(
-        initialBorrows.sub(new anchor.BN(50 * 1e9))
+        initialBorrows.sub(new anchor.BN(50 * 1e5))
       ),
       "Market total borrow assets should decrease by 50"
     );
@@ -358,14 +358,14 @@ describe("Repay", () => {
     // Verify bob's final quote balance is correct
     assert.equal(
       finalBobQuoteBalance,
-      BigInt(150 * 1e9), // Started with 100, borrowed 50
+      BigInt(150 * 1e5), // Started with 100, borrowed 50
       "Bob's final quote balance should be 150 tokens"
     );
 
     // Verify larry's final quote balance is correct
     assert.equal(
       finalLarryQuoteBalance,
-      BigInt(50 * 1e9), // Started with 100, repaid 50
+      BigInt(50 * 1e5), // Started with 100, repaid 50
       "Larry's final quote balance should be 50 tokens"
     );
   });
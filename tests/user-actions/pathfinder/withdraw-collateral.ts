@@ -32,7 +32,7 @@ describe("Withdraw Collateral", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 10 ** 5),
       conf: new anchor.BN(10 * 1e5),
       expo: -5,
@@ -33,7 +33,7 @@ describe("Withdraw", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0),
+      maxBorrowLtv: new anchor.BN(0),
       price: new anchor.BN(100 * 10 ** 9),
       conf: new anchor.BN(100 / 10 * 10 ** 9),
       expo: -9,
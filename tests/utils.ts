@@ -67,7 +67,7 @@ function getFeedIdFromHex(input: string): Buffer {
 export function deriveMarketAddress(
   quoteMint: PublicKey,
   collateralMint: PublicKey,
-  ltvFactor: anchor.BN,
+  maxBorrowLtv: anchor.BN,
   oracleId: PublicKey,
   programId: PublicKey
 ) {
@@ -77,7 +77,7 @@ export function deriveMarketAddress(
       Buffer.from("market"),
       quoteMint.toBuffer(),
       collateralMint.toBuffer(),
-      Buffer.from(ltvFactor.toArray("le", 8)),
+      Buffer.from(maxBorrowLtv.toArray("le", 8)),
       oracleId.toBuffer(),
     ],
     programId
@@ -231,22 +231,26 @@ export class TestUtils {
   public async createMarket(
     {
       symbol,
-      ltvFactor,
+      maxBorrowLtv,
       price,
       conf,
       expo,
       feeRecipient,
       authority,
       oracleSource = OracleSource.PythPull,
+      quotePrice,
+      quoteConf,
     }: {
       symbol: string,
-      ltvFactor: anchor.BN,
+      maxBorrowLtv: anchor.BN,
       price: anchor.BN,
       conf: anchor.BN,
       expo: number,
       feeRecipient: UserFixture,
       authority: UserFixture,
       oracleSource?: OracleSource,
+      quotePrice?: anchor.BN,
+      quoteConf?: anchor.BN,
     }
   ) {
     const collateral = new CollateralFixture(
@@ -254,14 +258,16 @@ export class TestUtils {
       this.program,
       this.provider,
       this.collateralMint,
-      ltvFactor,
+      maxBorrowLtv,
       oracleSource
     );
 
     await collateral.initPrice({
       price,
       conf,
-      expo
+      expo,
+      quotePrice,
+      quoteConf,
     });
 
     return new MarketFixture(
@@ -298,6 +304,10 @@ export class TestUtils {
     this.context.setClock(newClock);
   }
 
+  public async warpToSlot(slot: number): Promise<void> {
+    this.context.warpToSlot(BigInt(slot));
+  }
+
   public async getTime(): Promise<number> {
     const currentClock = await this.context.banksClient.getClock();
     return Number(currentClock.unixTimestamp);
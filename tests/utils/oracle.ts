@@ -1,5 +1,5 @@
 import { TestUtils } from "../utils";
-import { MarketFixture, OracleSource, UserFixture } from "../fixtures";
+import { DEFAULT_ORACLE_CONFIG, MarketFixture, OracleSource, UserFixture } from "../fixtures";
 import * as anchor from "@coral-xyz/anchor";
 import assert from "assert";
 
@@ -39,7 +39,7 @@ describe("Oracle", () => {
     // 0.000017905 * 1e9 = 17905
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(10 * 1e9),
       expo: -9,
@@ -89,7 +89,7 @@ describe("Oracle", () => {
     // 0.000017905 * 1e9 = 17905
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(17905),
       conf: new anchor.BN(0),
       expo: -9,
@@ -127,7 +127,7 @@ describe("Oracle", () => {
     // 0.000017905 * 1e9 = 17905
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(100 * 1e9),
       conf: new anchor.BN(10 * 1e9),
       expo: -9,
@@ -162,7 +162,223 @@ describe("Oracle", () => {
   });
 
   it("switchboard borrow fails if price is stale", async () => {
-    // TODO: Fixme
+
+    // 60 seconds is 150 slots, the median submission landed at slot 0
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.SwitchboardPull,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxAge: new anchor.BN(60) },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000_000 * 1e9),
+      owner: bob,
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(1),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await test.warpToSlot(151);
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Stale oracle");
+        return true;
+      }
+    );
+  });
+
+  it("switchboard borrow fails with too few live responses", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.SwitchboardPull,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxAge: new anchor.BN(60), minSbResponses: 2 },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000_000 * 1e9),
+      owner: bob,
+    });
+
+    // only the median submission is refreshed, the others landed at slot 0
+    await market.collateral.setSwitchboardLandedAt([1_000]);
+    await test.warpToSlot(1_000);
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Not enough oracle responses");
+        return true;
+      }
+    );
+
+    // a fresh second submission restores the quorum
+    await market.collateral.setSwitchboardLandedAt([1_000, 1_000]);
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(2),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+  });
+
+  it("switchboard borrow fails if the feed requires fewer responses than the market", async () => {
+
+    // the mainnet feed only requires 2 responses
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.SwitchboardPull,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, minSbResponses: 3 },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000_000 * 1e9),
+      owner: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Not enough oracle responses");
+        return true;
+      }
+    );
+  });
+
+  it("switchboard borrow fails if the std dev is too wide", async () => {
+
+    // the mainnet feed std dev is about 4 bps of its median
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.SwitchboardPull,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxConfBps: new anchor.BN(1) },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000_000 * 1e9),
+      owner: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle confidence too wide");
+        return true;
+      }
+    );
   });
 
 
@@ -170,7 +386,7 @@ describe("Oracle", () => {
 
     market = await test.createMarket({
       symbol: "BONK",
-      ltvFactor: new anchor.BN(0.8 * 1e9),
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
       price: new anchor.BN(17905),
       conf: new anchor.BN(0),
       expo: -9,
@@ -209,8 +425,612 @@ describe("Oracle", () => {
         });
       },
       (err: anchor.AnchorError) => {
-        assert.strictEqual(err.error.errorCode.number, 6010);
-        assert.strictEqual(err.error.errorMessage, "Math Underflow");
+        assert.strictEqual(err.error.errorMessage, "Oracle confidence too wide");
+        return true;
+      }
+    );
+  });
+
+  it("pyth borrow fails if confidence exceeds the market limit", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(1 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxConfBps: new anchor.BN(200) },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    // 1% confidence is within the 2% limit
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(1 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(3 * 1e9),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle confidence too wide");
+        return true;
+      }
+    );
+  });
+
+  it("pyth borrow fails if price is older than the market max age", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxAge: new anchor.BN(60) },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    await test.moveTimeForward(61);
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        // raised by the pyth receiver sdk
+        assert.strictEqual(err.error.errorMessage, "This price feed update's age exceeds the requested maximum age");
+        return true;
+      }
+    );
+  });
+
+  it("composite borrow prices collateral through the quote feed", async () => {
+
+    // $100 collateral against a $2 quote token is worth 50 quote
+    market = await test.createMarket({
+      symbol: "SOL",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.Composite,
+      quotePrice: new anchor.BN(2 * 1e9),
+      quoteConf: new anchor.BN(0),
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(10 * 1e9),
+      owner: bob,
+    });
+
+    // 10 * 50 * 0.8 = 400 quote max
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(390 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(20 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+  });
+
+  it("composite borrow uses the conservative side of both feeds", async () => {
+
+    // lower bound is (100 - 10) / (2 + 0.25) = 40 quote
+    market = await test.createMarket({
+      symbol: "SOL",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(10 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.Composite,
+      quotePrice: new anchor.BN(2 * 1e9),
+      quoteConf: new anchor.BN(0.25 * 1e9),
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(10 * 1e9),
+      owner: bob,
+    });
+
+    // 10 * 40 * 0.8 = 320 quote max
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(321 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(319 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+  });
+
+  it("composite borrow fails when the ratio truncates to zero", async () => {
+
+    // 1e-9 collateral against a $2 quote token rounds down to no price at all
+    market = await test.createMarket({
+      symbol: "SOL",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(1),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+      oracleSource: OracleSource.Composite,
+      quotePrice: new anchor.BN(2 * 1e9),
+      quoteConf: new anchor.BN(0),
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle price is not positive");
+        return true;
+      }
+    );
+  });
+
+  it("pyth borrow fails on a negative price", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({ user: larry });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    await market.collateral.setPrice({
+      price: new anchor.BN(-100 * 1e9),
+      conf: new anchor.BN(0),
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle price is not positive");
+        return true;
+      }
+    );
+  });
+
+  it("pyth lower bound saturates at the price floor", async () => {
+
+    // confidence as wide as the price, lower bound is held at 50%
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(100 * 1e9),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, priceFloorBps: new anchor.BN(5_000) },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(10 * 1e9),
+      owner: bob,
+    });
+
+    // 10 * 50 * 0.8 = 400 quote max
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(390 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(20 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "User is not solvent");
+        return true;
+      }
+    );
+  });
+
+  it("pyth borrow fails over to the fallback when the primary is stale", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.collateral.initFallbackPrice({
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+      oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxAge: new anchor.BN(60) },
+      fallbackOracle: {
+        id: market.collateral.getFallbackOracleId(),
+        source: { pythPull: {} },
+        maxDeviationBps: new anchor.BN(500),
+      },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    await test.moveTimeForward(61);
+
+    // only the secondary feed is refreshed
+    await market.collateral.setFallbackPrice({
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+    });
+
+    await market.borrow({
+      user: bob,
+      amount: new anchor.BN(1 * 1e9),
+      shares: new anchor.BN(0),
+      owner: bob,
+      recipient: bob,
+    });
+  });
+
+  it("pyth borrow fails if the fallback deviates from the primary", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.collateral.initFallbackPrice({
+      price: new anchor.BN(120 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+    });
+
+    await market.setFallbackOracle({
+      user: larry,
+      fallbackOracle: {
+        id: market.collateral.getFallbackOracleId(),
+        source: { pythPull: {} },
+        maxDeviationBps: new anchor.BN(1_000),
+      },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle prices deviate too much");
+        return true;
+      }
+    );
+  });
+
+  it("pyth borrow fails with a fallback account for another feed", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await market.collateral.initFallbackPrice({
+      price: new anchor.BN(120 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+    });
+
+    await market.createAndSetAuthority({
+      user: larry,
+    });
+
+    await market.setFallbackOracle({
+      user: larry,
+      fallbackOracle: {
+        id: market.collateral.getFallbackOracleId(),
+        source: { pythPull: {} },
+        maxDeviationBps: new anchor.BN(1_000),
+      },
+    });
+
+    await market.deposit({
+      user: larry,
+      amount: new anchor.BN(1_000 * 1e9),
+      shares: new anchor.BN(0),
+      owner: larry
+    });
+
+    await market.depositCollateral({
+      user: bob,
+      amount: new anchor.BN(1_000 * 1e9),
+      owner: bob,
+    });
+
+    // the primary feed in place of the deviating fallback
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+          fallbackOracleAi: market.collateral.getOracleAccount(),
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle account does not match the market feed");
+        return true;
+      }
+    );
+
+    // an account the pyth receiver doesn't own
+    await assert.rejects(
+      async () => {
+        await market.borrow({
+          user: bob,
+          amount: new anchor.BN(1 * 1e9),
+          shares: new anchor.BN(0),
+          owner: bob,
+          recipient: bob,
+          fallbackOracleAi: bob.quoteAta,
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Oracle account owner is invalid");
+        return true;
+      }
+    );
+  });
+
+  it("market creation fails with an invalid oracle config", async () => {
+
+    market = await test.createMarket({
+      symbol: "BONK",
+      maxBorrowLtv: new anchor.BN(0.8 * 1e9),
+      price: new anchor.BN(100 * 1e9),
+      conf: new anchor.BN(0),
+      expo: -9,
+      feeRecipient: futarchy,
+      authority: futarchy,
+    });
+
+    await assert.rejects(
+      async () => {
+        await market.createAndSetAuthority({
+          user: larry,
+          oracleConfig: { ...DEFAULT_ORACLE_CONFIG, maxAge: new anchor.BN(2 * 3600) },
+        });
+      },
+      (err: anchor.AnchorError) => {
+        assert.strictEqual(err.error.errorMessage, "Invalid oracle config");
         return true;
       }
     );
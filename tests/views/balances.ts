@@ -114,7 +114,7 @@ async function _generatePendingInterest({
 
   let market = await test.createMarket({
     symbol: "BONK",
-    ltvFactor: new anchor.BN(0.8 * 1e9),
+    maxBorrowLtv: new anchor.BN(0.8 * 1e9),
     price: new anchor.BN(100 * 1e9),
     conf: new anchor.BN(0),
     expo: -9,
@@ -143,7 +143,7 @@ async function _generatePendingInterest({
 
   const collateralAmount = amountBorrowed
     .mul(new anchor.BN(1e9)) // price scale
-    .div(market.collateral.ltvFactor)
+    .div(market.collateral.maxBorrowLtv)
     .mul(new anchor.BN(1e9)) // price scale
     .div(new anchor.BN(100 * 1e9)); // price
 